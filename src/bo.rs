@@ -0,0 +1,146 @@
+use crate::farfalle::DomainSuffix;
+use crate::xoofff::{ct_eq, Xoofff};
+
+/// Byte length of authentication tag produced by Xoofff-BO
+pub const TAG_LEN: usize = 16;
+
+/// Xoofff-BO is a one-shot nonce-based authenticated encryption with associated data scheme,
+/// obtained by instantiating Deck-BO mode with Xoofff deck function. Keystream and tag are
+/// derived from disjoint deck function calls, which are domain separated by a suffix bit
+/// appended to nonce, following encrypt-then-MAC paradigm
+///
+/// - C <- P + F_K(N || 1)
+/// - T <- 0^t + F_K(C ◦ A ◦ N || 0)
+///
+/// s.t. nonce N must never be repeated under same key. Masked key is derived only once and
+/// the deck function is cloned for both calls.
+///
+/// When `aead` feature is enabled, this type also implements `aead::AeadInPlace` and
+/// `aead::KeyInit` traits, with 32 -bytes key, 16 -bytes nonce and 16 -bytes tag.
+#[derive(Clone, Copy)]
+pub struct XoofffBo {
+    deck: Xoofff, // deck function, with masked key derived, ready to absorb
+}
+
+impl XoofffBo {
+    /// Create a new instance of Xoofff-BO, with a key of arbitrary byte length.
+    #[inline(always)]
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            deck: Xoofff::new(key),
+        }
+    }
+
+    /// Given nonce N and associated data A, both of arbitrary byte length, this routine encrypts
+    /// plaintext P in-place, returning authentication tag T, computed over N, A and C.
+    #[inline(always)]
+    pub fn encrypt(&self, nonce: &[u8], ad: &[u8], buf: &mut [u8]) -> [u8; TAG_LEN] {
+        self.apply_keystream(nonce, buf);
+        self.authenticate(nonce, ad, buf)
+    }
+
+    /// Given nonce N, associated data A and authentication tag T, this routine verifies T in
+    /// constant-time over N, A and ciphertext C, and only if it matches, decrypts C in-place,
+    /// returning truth value. Otherwise buffer is left untouched and false is returned.
+    #[inline(always)]
+    pub fn decrypt(&self, nonce: &[u8], ad: &[u8], buf: &mut [u8], tag: &[u8; TAG_LEN]) -> bool {
+        let computed = self.authenticate(nonce, ad, buf);
+        if !ct_eq(&computed, tag) {
+            return false;
+        }
+
+        self.apply_keystream(nonce, buf);
+        true
+    }
+
+    /// XORs keystream F_K(N || 1) into buffer.
+    #[inline(always)]
+    fn apply_keystream(&self, nonce: &[u8], buf: &mut [u8]) {
+        let mut deck = self.deck;
+        deck.absorb(nonce);
+        deck.finalize_suffix(DomainSuffix::from_bits(&[true]).unwrap(), 0);
+
+        let mut blk = [0u8; 48];
+        for chunk in buf.chunks_mut(blk.len()) {
+            let ks = &mut blk[..chunk.len()];
+            deck.squeeze(ks);
+
+            for (b, k) in chunk.iter_mut().zip(ks.iter()) {
+                *b ^= *k;
+            }
+        }
+    }
+
+    /// Computes tag T = 0^t + F_K(C ◦ A ◦ N || 0).
+    #[inline(always)]
+    fn authenticate(&self, nonce: &[u8], ad: &[u8], ct: &[u8]) -> [u8; TAG_LEN] {
+        let mut deck = self.deck;
+        let mut tag = [0u8; TAG_LEN];
+
+        deck.absorb(nonce);
+        deck.finalize_suffix(DomainSuffix::from_bits(&[false]).unwrap(), 0);
+        deck.restart();
+
+        deck.absorb(ad);
+        deck.finalize_suffix(DomainSuffix::EMPTY, 0);
+        deck.restart();
+
+        deck.absorb(ct);
+        deck.finalize_suffix(DomainSuffix::EMPTY, 0);
+        deck.squeeze(&mut tag);
+
+        tag
+    }
+}
+
+#[cfg(feature = "aead")]
+mod traits {
+    use super::{XoofffBo, TAG_LEN};
+    use aead::consts::{U0, U16, U32};
+    use aead::{AeadCore, AeadInPlace, Error, Key, KeyInit, KeySizeUser, Nonce, Tag};
+
+    impl KeySizeUser for XoofffBo {
+        type KeySize = U32;
+    }
+
+    impl KeyInit for XoofffBo {
+        #[inline(always)]
+        fn new(key: &Key<Self>) -> Self {
+            XoofffBo::new(key)
+        }
+    }
+
+    impl AeadCore for XoofffBo {
+        type NonceSize = U16;
+        type TagSize = U16;
+        type CiphertextOverhead = U0;
+    }
+
+    impl AeadInPlace for XoofffBo {
+        fn encrypt_in_place_detached(
+            &self,
+            nonce: &Nonce<Self>,
+            associated_data: &[u8],
+            buffer: &mut [u8],
+        ) -> aead::Result<Tag<Self>> {
+            let tag = self.encrypt(nonce, associated_data, buffer);
+            Ok(tag.into())
+        }
+
+        fn decrypt_in_place_detached(
+            &self,
+            nonce: &Nonce<Self>,
+            associated_data: &[u8],
+            buffer: &mut [u8],
+            tag: &Tag<Self>,
+        ) -> aead::Result<()> {
+            let tag: [u8; TAG_LEN] = (*tag).into();
+
+            if self.decrypt(nonce, associated_data, buffer, &tag) {
+                Ok(())
+            } else {
+                Err(Error)
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::farfalle::DomainSuffix;
+use crate::xoofff::Xoofff;
+use std::marker::PhantomData;
+
+/// Phase of deck function, in which message bytes can be absorbed into its state.
+#[derive(Clone, Copy)]
+pub struct Absorbing;
+
+/// Phase of deck function, in which output bytes can be squeezed out of its finalized state.
+#[derive(Clone, Copy)]
+pub struct Squeezing;
+
+/// Typestate wrapper over Xoofff deck function s.t. phase of `absorb->finalize->squeeze`
+/// cycle is encoded in type of the object, instead of being tracked at runtime. Finalizing
+/// consumes `Deck<Absorbing>`, returning `Deck<Squeezing>`, while restarting converts it back,
+/// so that absorbing into finalized state ( or squeezing from non-finalized state ) is a
+/// compile-time error, instead of being silently ignored.
+///
+/// Both this type and `Xoofff` share same core, so they produce exactly same output bytes.
+#[derive(Clone, Copy)]
+pub struct Deck<S> {
+    core: Xoofff,           // deck function, phase of which always matches S
+    _phase: PhantomData<S>, // phase of `absorb->finalize->squeeze` cycle
+}
+
+impl Deck<Absorbing> {
+    /// Create a new instance of deck function, with a key of arbitrary byte length, which is
+    /// ready to absorb message bytes.
+    #[inline(always)]
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            core: Xoofff::new(key),
+            _phase: PhantomData,
+        }
+    }
+
+    /// Create a new instance of deck function, with a key of arbitrary byte length, which is
+    /// customized by a customization string of arbitrary byte length.
+    #[inline(always)]
+    pub fn new_customized(key: &[u8], custom: &[u8]) -> Self {
+        Self {
+            core: Xoofff::new_customized(key, custom),
+            _phase: PhantomData,
+        }
+    }
+
+    /// Given a message M of byte length N (>=0), absorbs it into deck function state. Can be
+    /// called arbitrary many times, before state is finalized.
+    #[inline(always)]
+    pub fn absorb(&mut self, msg: &[u8]) {
+        self.core.absorb(msg);
+    }
+
+    /// Given a message M of bit length N (>=0), absorbs it into deck function state s.t. last
+    /// byte of M can be partial.
+    #[inline(always)]
+    pub fn absorb_bits(&mut self, msg: &[u8], bit_len: usize) {
+        self.core.absorb_bits(msg, bit_len);
+    }
+
+    /// Given a tuple of messages, absorbs them into deck function state, each being encoded
+    /// injectively, by prepending its length.
+    #[inline(always)]
+    pub fn absorb_tuple(&mut self, msgs: &[&[u8]]) {
+        self.core.absorb_tuple(msgs);
+    }
+
+    /// Finalizes deck function state with a ( possibly multi-byte ) domain suffix, consuming
+    /// this object and returning one, which can be used for squeezing arbitrary many output
+    /// bytes. Squeezing byte offset must be <= 48.
+    #[inline(always)]
+    pub fn finalize(mut self, suffix: DomainSuffix, offset: usize) -> Deck<Squeezing> {
+        self.core.finalize_suffix(suffix, offset);
+
+        Deck {
+            core: self.core,
+            _phase: PhantomData,
+        }
+    }
+}
+
+impl Deck<Squeezing> {
+    /// Squeezes arbitrary many bytes out of finalized deck function state. Can be called
+    /// arbitrary many times, before state is restarted.
+    #[inline(always)]
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        self.core.squeeze(out);
+    }
+
+    /// Returns byte position of next byte to be squeezed, in deck function output stream.
+    #[inline(always)]
+    pub fn position(&self) -> u64 {
+        self.core.position()
+    }
+
+    /// Seeks to arbitrary byte position in deck function output stream, either forwards or
+    /// backwards, so that next squeezed byte is the one at that position.
+    #[inline(always)]
+    pub fn seek(&mut self, pos: u64) {
+        self.core.seek(pos);
+    }
+
+    /// Restarts `absorb->finalize->squeeze` cycle, consuming this object and returning one,
+    /// which can be used for absorbing next message of the sequence.
+    #[inline(always)]
+    pub fn restart(mut self) -> Deck<Absorbing> {
+        self.core.restart();
+
+        Deck {
+            core: self.core,
+            _phase: PhantomData,
+        }
+    }
+}
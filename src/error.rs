@@ -0,0 +1,51 @@
+use std::fmt;
+
+/// Errors, which can be returned by fallible routines ( prefixed with `try_` ) of deck function
+/// API, instead of panicking or silently ignoring the request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XoofffError {
+    /// Byte offset, considered during squeezing, must be <= BLOCK_SIZE, holds offending offset
+    BadOffset(usize),
+    /// Domain separator bit width must be <= 7 ( or <= 64, for multi-byte domain suffix ),
+    /// holds offending bit width
+    BadDomainSeparatorWidth(usize),
+    /// Domain separator has bits set beyond its declared bit width, holds offending bits
+    BadDomainSeparatorBits(u64),
+    /// Attempted to absorb after finalization or to squeeze before it
+    WrongPhase,
+    /// Message block, absorbed out of order, is given more than once, holds its index
+    DuplicateBlock(u64),
+    /// Message block, absorbed out of order, is never given, holds its index
+    MissingBlock(u64),
+    /// Message block, absorbed out of order, is beyond declared block count, holds its index
+    BadBlockIndex(u64),
+    /// Partial accumulator, ending with a partial block ( i.e. end of the string ), is followed by
+    /// another range, when merging, holds index of that partial block
+    TrailingPartialBlock(u64),
+    /// Serialized partial accumulator is malformed
+    BadEncoding,
+}
+
+impl fmt::Display for XoofffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadOffset(off) => write!(f, "bad squeezing byte offset {}", off),
+            Self::BadDomainSeparatorWidth(width) => {
+                write!(f, "bad domain separator bit width {}", width)
+            }
+            Self::BadDomainSeparatorBits(bits) => {
+                write!(f, "domain separator bits {:#x} exceed declared width", bits)
+            }
+            Self::WrongPhase => write!(f, "deck function is in wrong phase"),
+            Self::DuplicateBlock(idx) => write!(f, "duplicate message block at index {}", idx),
+            Self::MissingBlock(idx) => write!(f, "missing message block at index {}", idx),
+            Self::BadBlockIndex(idx) => write!(f, "out of range message block index {}", idx),
+            Self::TrailingPartialBlock(idx) => {
+                write!(f, "partial message block at index {} isn't last", idx)
+            }
+            Self::BadEncoding => write!(f, "malformed serialized partial accumulator"),
+        }
+    }
+}
+
+impl std::error::Error for XoofffError {}
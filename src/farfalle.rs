@@ -0,0 +1,1182 @@
+use crate::error::XoofffError;
+use std::cmp;
+
+#[cfg(feature = "rayon")]
+mod par;
+use std::collections::BTreeSet;
+use std::marker::PhantomData;
+
+/// \# -of independent permutation states, which are permuted together, using `permute_many`,
+/// when absorbing ( or squeezing ) whole message blocks
+const PERMUTE_BATCH: usize = 16;
+
+/// Byte length of digest H, which is placed at start of the block, permuted into a derived masked key
+const DERIVED_KEY_LEN: usize = 32;
+
+/// Last byte of the block, permuted into masked key, derived from a key of byte length >= BLOCK_SIZE.
+/// Padded short keys end with 0x00 or 0x01, so that both blocks ( hence masked keys ) never coincide.
+const LONG_KEY_TAG: u8 = 0x02;
+
+/// Last byte of the block, permuted into masked key of a customized deck function, which never
+/// coincides with the one of a padded short key or a long key.
+const CUSTOMIZED_KEY_TAG: u8 = 0x03;
+
+/// Frame bits ( seven 1 -bits ), appended to encoded customization string, when it's absorbed into
+/// the deck function, keyed with the secret key. They're reserved for deriving customized masked keys,
+/// so modes of use must not use them, as domain suffix.
+const CUSTOMIZATION_SUFFIX: [bool; 7] = [true; 7];
+
+/// A b -bit cryptographic permutation, which can be used for instantiating Farfalle
+/// construction. Messages are consumed and output is produced in b/8 -bytes chunks,
+/// which are mapped to ( and from ) permutation state by `from_bytes` ( and `to_bytes` ).
+///
+/// Farfalle, as defined in https://ia.cr/2016/1188, can use four different permutations
+/// p_b, p_c, p_d and p_e, but both Xoofff and Kravatte use same permutation for all of
+/// them, so only one is required here.
+pub trait Permutation {
+    /// Byte width of permutation state, which is also the message block size
+    const BLOCK_SIZE: usize;
+
+    /// Permutation state, represented as words, which permutation natively works on
+    type State: Copy;
+
+    /// Byte array of length BLOCK_SIZE, holding serialized permutation state
+    type Block: Copy + AsRef<[u8]> + AsMut<[u8]>;
+
+    /// Permutation state, with all bits set to zero
+    const ZERO_STATE: Self::State;
+
+    /// Message block, with all bytes set to zero
+    const ZERO_BLOCK: Self::Block;
+
+    /// Applies permutation on state.
+    fn permute(state: &mut Self::State);
+
+    /// Applies permutation on each of many independent states. Backends, which are able to
+    /// permute multiple states at once ( e.g. using SIMD ), override it, while by default,
+    /// states are permuted one after another.
+    #[inline(always)]
+    fn permute_many(states: &mut [Self::State]) {
+        states.iter_mut().for_each(Self::permute);
+    }
+
+    /// Interprets bytes of a message block as permutation state.
+    fn from_bytes(block: &Self::Block) -> Self::State;
+
+    /// Serializes permutation state into bytes of a message block.
+    fn to_bytes(state: &Self::State, block: &mut Self::Block);
+
+    /// XORs source state into destination state.
+    fn xor(dst: &mut Self::State, src: &Self::State);
+
+    /// Computes 32 -bytes digest of a message of arbitrary byte length, using a collision resistant
+    /// hash function, built on same permutation ( family ), which compresses keys of byte length
+    /// >= BLOCK_SIZE, so that they can be permuted into masked key.
+    fn digest(msg: &[u8]) -> [u8; 32];
+}
+
+/// A rolling function, which is an invertible ( and cheap to compute ) transformation of
+/// permutation state, used for deriving input masks ( roll_c ) and output masks ( roll_e )
+/// in Farfalle construction, see section 3 of https://ia.cr/2016/1188.
+pub trait Rolling<S> {
+    /// Applies rolling function once, on permutation state.
+    fn roll(state: &mut S);
+
+    /// Applies rolling function n times, on permutation state. Rolling functions, which can
+    /// jump ahead faster than applying them sequentially, should override this routine.
+    #[inline(always)]
+    fn roll_n(state: &mut S, n: u64) {
+        for _ in 0..n {
+            Self::roll(state);
+        }
+    }
+}
+
+/// Domain suffix, which is appended to last message of the sequence, right before padding, when
+/// deck function state is finalized. It can be at max 64 -bits wide s.t. modes can append longer
+/// frame bits, spanning over multiple bytes.
+///
+/// Bits are appended in little endian order i.e. bit i of `bits` is the i -th appended bit, same
+/// as how domain separator of `finalize` is interpreted. To avoid getting bit order wrong, prefer
+/// constructing suffix from sequence of bits, in order they're appended, using `from_bits`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DomainSuffix {
+    bits: u64,    // suffix bits, first appended bit being least significant one
+    width: usize, // number of suffix bits
+}
+
+impl DomainSuffix {
+    /// Empty domain suffix, appending no bits.
+    pub const EMPTY: Self = Self { bits: 0, width: 0 };
+
+    /// Maximum bit width of domain suffix
+    pub const MAX_WIDTH: usize = 64;
+
+    /// Create a new domain suffix, from bit width (<= 64) and bits, interpreted in little endian
+    /// order, returning error if any bit beyond bit width is set.
+    #[inline(always)]
+    pub const fn new(bits: u64, width: usize) -> Result<Self, XoofffError> {
+        if width > Self::MAX_WIDTH {
+            return Err(XoofffError::BadDomainSeparatorWidth(width));
+        }
+        if width < Self::MAX_WIDTH && (bits >> width) != 0 {
+            return Err(XoofffError::BadDomainSeparatorBits(bits));
+        }
+
+        Ok(Self { bits, width })
+    }
+
+    /// Create a new domain suffix, from sequence of (<= 64) bits, in order they're appended.
+    #[inline(always)]
+    pub fn from_bits(bits: &[bool]) -> Result<Self, XoofffError> {
+        if bits.len() > Self::MAX_WIDTH {
+            return Err(XoofffError::BadDomainSeparatorWidth(bits.len()));
+        }
+
+        let word = bits
+            .iter()
+            .enumerate()
+            .fold(0u64, |acc, (i, &b)| acc | ((b as u64) << i));
+
+        Self::new(word, bits.len())
+    }
+
+    /// Returns suffix bits, first appended bit being least significant one.
+    #[inline(always)]
+    pub const fn bits(&self) -> u64 {
+        self.bits
+    }
+
+    /// Returns number of suffix bits.
+    #[inline(always)]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+}
+
+/// Masked key of Farfalle based deck function, which is derived only once from the key, so that
+/// arbitrary many independent deck functions can be instantiated from it, by calling `deck`,
+/// without paying the cost of applying permutation again. It's `Send + Sync` s.t. it can be
+/// shared across worker threads, each instantiating its own deck function.
+#[derive(Clone, Copy)]
+pub struct FarfalleKey<P: Permutation, Rc, Re> {
+    masked_key: P::State,               // masked key i.e. input mask, at start
+    _rolling: PhantomData<(P, Rc, Re)>, // permutation and rolling functions
+}
+
+impl<P, Rc, Re> FarfalleKey<P, Rc, Re>
+where
+    P: Permutation,
+    Rc: Rolling<P::State>,
+    Re: Rolling<P::State>,
+{
+    /// Derives masked key, from a key of arbitrary byte length.
+    ///
+    /// Masked key is derived as k = P(pad10*(K)), for keys of byte length < BLOCK_SIZE, as
+    /// Farfalle specification defines. Longer keys don't fit in a padded block, so they're
+    /// compressed, using collision resistant hash function H ( see `Permutation::digest` ), s.t.
+    /// masked key is k = P(H(K) || 0* || 0x02). Two long keys result in same masked key only
+    /// if they collide under H, while as P is a permutation and last byte of a padded short key
+    /// is either 0x00 or 0x01, a long key never results in same masked key as any short key does.
+    #[inline(always)]
+    pub fn new(key: &[u8]) -> Self {
+        if key.len() >= P::BLOCK_SIZE {
+            return Self::from_digest(&P::digest(key), LONG_KEY_TAG);
+        }
+
+        let padded_key = pad10x::<P>(key);
+        let mut masked_key = P::from_bytes(&padded_key);
+        P::permute(&mut masked_key);
+
+        Self {
+            masked_key,
+            _rolling: PhantomData,
+        }
+    }
+
+    /// Derives masked key, from a key and a customization string S, both of arbitrary byte
+    /// length, s.t. k = P(H || 0* || 0x03), where H is first 32 -bytes of F_K((S) || 1111111),
+    /// computed using the deck function keyed with K, absorbing S as a tuple, see `absorb_tuple`,
+    /// with reserved frame bits appended. As H is output of the deck function, keyed with the
+    /// secret key, finding two customization strings, resulting in same masked key, is as hard
+    /// as distinguishing the deck function from a random one. Last byte of the block never matches
+    /// the one of a padded key ( or of a long key ), so masked key is different from the one
+    /// derived by `new`, for any key.
+    #[inline(always)]
+    pub fn new_customized(key: &[u8], custom: &[u8]) -> Self {
+        let mut deck = Self::new(key).deck();
+        let mut dig = [0u8; DERIVED_KEY_LEN];
+
+        // frame bits are at most 7 -bits wide
+        let suffix = DomainSuffix::from_bits(&CUSTOMIZATION_SUFFIX).unwrap();
+
+        deck.absorb_tuple(&[custom]);
+        deck.finalize_suffix(suffix, 0);
+        deck.squeeze(&mut dig);
+
+        Self::from_digest(&dig, CUSTOMIZED_KEY_TAG)
+    }
+
+    /// Derives masked key k = P(H || 0* || tag), from a digest H of DERIVED_KEY_LEN -bytes.
+    #[inline(always)]
+    fn from_digest(dig: &[u8; DERIVED_KEY_LEN], tag: u8) -> Self {
+        debug_assert!(
+            P::BLOCK_SIZE > DERIVED_KEY_LEN + 1,
+            "Block size must be > {} -bytes",
+            DERIVED_KEY_LEN + 1
+        );
+
+        let mut blk = P::ZERO_BLOCK;
+        blk.as_mut()[..DERIVED_KEY_LEN].copy_from_slice(dig);
+        blk.as_mut()[P::BLOCK_SIZE - 1] = tag;
+
+        let mut masked_key = P::from_bytes(&blk);
+        P::permute(&mut masked_key);
+
+        Self {
+            masked_key,
+            _rolling: PhantomData,
+        }
+    }
+
+    /// Instantiates a new deck function, ready to absorb message bytes, with already derived
+    /// masked key, at zero permutation cost.
+    #[inline(always)]
+    pub fn deck(&self) -> Farfalle<P, Rc, Re> {
+        Farfalle {
+            imask: self.masked_key,
+            omask: P::ZERO_STATE,
+            fmask: P::ZERO_STATE,
+            acc: P::ZERO_STATE,
+            iblk: P::ZERO_BLOCK,
+            oblk: P::ZERO_BLOCK,
+            ioff: 0,
+            pbyte: 0,
+            pbits: 0,
+            ooff: 0,
+            oidx: 0,
+            finalized: usize::MIN,
+            _rolling: PhantomData,
+        }
+    }
+}
+
+/// Farfalle is a construction for building deck functions, from a b -bit permutation P and
+/// two rolling functions Rc, Re, having nice incremental input/ output processing capability,
+/// offering ability of restarting `absorb->finalize->squeeze` cycle arbitrary number of times,
+/// so that arbitrary number of message sequences ( s.t. each message itself is arbitrary bytes
+/// wide ) can be consumed in very flexible fashion.
+///
+/// Both Xoofff and Kravatte are instantiations of this construction, while one can also plug
+/// in experimental permutations and rolling functions, by implementing `Permutation` and
+/// `Rolling` traits.
+///
+/// See https://ia.cr/2016/1188 for definition of Farfalle.
+#[derive(Clone, Copy)]
+pub struct Farfalle<P: Permutation, Rc, Re> {
+    imask: P::State,                    // input mask
+    omask: P::State,                    // output mask
+    fmask: P::State,                    // output mask, right after finalization
+    acc: P::State,                      // accumulator
+    iblk: P::Block,                     // input message block ( buffer )
+    oblk: P::Block,                     // output message block ( buffer )
+    ioff: usize,                        // offset into input message block
+    pbyte: u8,                          // pending bits of partial input byte
+    pbits: usize,                       // number of pending bits (< 8)
+    ooff: usize,                        // offset into output message block
+    oidx: u64,                          // index of output message block ( buffer )
+    finalized: usize,                   // is deck function state finalized ?
+    _rolling: PhantomData<(P, Rc, Re)>, // permutation and rolling functions
+}
+
+impl<P, Rc, Re> Farfalle<P, Rc, Re>
+where
+    P: Permutation,
+    Rc: Rolling<P::State>,
+    Re: Rolling<P::State>,
+{
+    /// Create a new instance of deck function, with a key of arbitrary byte length, which
+    /// can be used for incrementally absorbing messages and squeezing output bytes.
+    ///
+    /// When many deck functions are to be instantiated with same key, consider deriving masked
+    /// key only once, using `FarfalleKey`, see `FarfalleKey::new` for how it's derived.
+    #[inline(always)]
+    pub fn new(key: &[u8]) -> Self {
+        FarfalleKey::new(key).deck()
+    }
+
+    /// Create a new instance of deck function, with a key of arbitrary byte length, which is
+    /// customized by a customization string S ( of arbitrary byte length ), similar to how KMAC
+    /// is. Customization is applied at key level i.e. masked key is derived from both K and S,
+    /// see `FarfalleKey::new_customized`, so that returned deck function is domain separated
+    /// from any deck function created using `new`, whatever strings are absorbed into it.
+    ///
+    /// As deck function is `Copy`, customized one can be cheaply cloned and reused as template.
+    #[inline(always)]
+    pub fn new_customized(key: &[u8], custom: &[u8]) -> Self {
+        FarfalleKey::new_customized(key, custom).deck()
+    }
+
+    /// Given a message M of byte length N (>=0), this routine can be used for absorbing
+    /// message bytes into the state of the deck function, following algorithm 1,
+    /// defined in Farfalle specification https://ia.cr/2016/1188.
+    ///
+    /// Note, this function can be called multiple times until deck function state is finalized.
+    /// Once finalized, bytes can be squeezed out of deck function state. Even after finalization
+    /// new absorption->finalization->squeezing phase can be started by calling restart function.
+    #[inline(always)]
+    pub fn absorb(&mut self, msg: &[u8]) {
+        if self.finalized == usize::MAX {
+            return;
+        }
+
+        if self.pbits == 0 {
+            self.absorb_bytes(msg);
+            return;
+        }
+
+        // message is not byte aligned anymore, so shift it by number of pending bits
+        let mut blk = P::ZERO_BLOCK;
+        for chunk in msg.chunks(P::BLOCK_SIZE) {
+            let dst = &mut blk.as_mut()[..chunk.len()];
+
+            for (d, &m) in dst.iter_mut().zip(chunk.iter()) {
+                *d = self.pbyte | (m << self.pbits);
+                self.pbyte = m >> (8 - self.pbits);
+            }
+
+            self.absorb_bytes(dst);
+        }
+    }
+
+    /// Given a message M of bit length N (>=0), this routine can be used for absorbing message
+    /// bits into the state of the deck function, same as `absorb` does, except that last byte
+    /// of M can be partial i.e. only its N mod 8 least significant bits are absorbed. Those
+    /// are kept pending, until more message bits arrive or state is finalized, when they're
+    /// merged with domain separator and padding.
+    ///
+    /// Note, this function can be called multiple times ( interleaved with `absorb` ) until deck
+    /// function state is finalized, bit string being absorbed is concatenation of all of them.
+    #[inline(always)]
+    pub fn absorb_bits(&mut self, msg: &[u8], bit_len: usize) {
+        debug_assert!(
+            bit_len <= msg.len() * 8,
+            "Bit length must be <= {} -bits",
+            msg.len() * 8
+        );
+
+        if self.finalized == usize::MAX {
+            return;
+        }
+
+        let byte_cnt = bit_len / 8;
+        let rm_bits = bit_len % 8;
+
+        self.absorb(&msg[..byte_cnt]);
+
+        if rm_bits > 0 {
+            let bits = (msg[byte_cnt] & ((1u8 << rm_bits) - 1)) as u16;
+            let pending = self.pbyte as u16 | (bits << self.pbits);
+
+            self.pbits += rm_bits;
+            if self.pbits >= 8 {
+                self.absorb_bytes(&[pending as u8]);
+                self.pbits -= 8;
+                self.pbyte = (pending >> 8) as u8;
+            } else {
+                self.pbyte = pending as u8;
+            }
+        }
+    }
+
+    /// Given a tuple of messages ( each of arbitrary byte length ), this routine absorbs them into
+    /// the state of the deck function, such that each message is encoded as `encode_string`
+    /// of NIST SP 800-185 i.e. left_encode(bit length of message) || message. The encoding is
+    /// injective, so that ("ab", "c") and ("a", "bc") always result in different input.
+    ///
+    /// Note, this function can be called multiple times, same as `absorb`.
+    #[inline(always)]
+    pub fn absorb_tuple(&mut self, msgs: &[&[u8]]) {
+        if self.finalized == usize::MAX {
+            return;
+        }
+
+        for msg in msgs {
+            let (enc, len) = left_encode((msg.len() as u64) * 8);
+            self.absorb(&enc[..len]);
+            self.absorb(msg);
+        }
+    }
+
+    /// Absorbs byte aligned message into input message block ( buffer ), compressing it into
+    /// accumulator, whenever buffer is full.
+    #[inline(always)]
+    fn absorb_bytes(&mut self, msg: &[u8]) {
+        let mut moff = 0;
+
+        // fill partially filled input message block ( if any ) first
+        if self.ioff > 0 {
+            moff = cmp::min(P::BLOCK_SIZE - self.ioff, msg.len());
+            self.iblk.as_mut()[self.ioff..(self.ioff + moff)].copy_from_slice(&msg[..moff]);
+            self.ioff += moff;
+
+            if self.ioff < P::BLOCK_SIZE {
+                return;
+            }
+
+            self.compress();
+            self.ioff = 0;
+        }
+
+        let blk_cnt = (msg.len() - moff) / P::BLOCK_SIZE;
+        let (body, tail) = msg[moff..].split_at(blk_cnt * P::BLOCK_SIZE);
+
+        Self::compress_blocks(&mut self.imask, &mut self.acc, body);
+
+        self.iblk.as_mut()[..tail.len()].copy_from_slice(tail);
+        self.ioff = tail.len();
+    }
+
+    /// Given that arbitrary many message bytes are already absorbed into deck function
+    /// state, this routine can be used for finalizing the state, so that arbitrary many
+    /// bytes can be squeezed out of deck function state.
+    ///
+    /// - Once finalized, calling this routine again on same object does nothing.
+    /// - Attempting to absorb new message bytes on already finalized state, does nothing.
+    /// - After finalization, one might start squeezing arbitrary many output bytes.
+    /// - After finishing squeezing, when new message arrives, arbitrary many bytes
+    ///   can be consumed into deck function state, by restarting `absorb->finalize->squeeze` cycle.
+    ///
+    /// This routine implements portion of algorithm 1 of https://ia.cr/2016/1188.
+    #[inline(always)]
+    pub fn finalize(&mut self, domain_seperator: u8, ds_bit_width: usize, offset: usize) {
+        debug_assert!(
+            ds_bit_width <= 7,
+            "Domain seperator bit width is not allowed to be > 7"
+        );
+        debug_assert!(
+            (domain_seperator as u16) >> ds_bit_width == 0,
+            "Domain seperator must not have bits set, beyond its bit width"
+        );
+        debug_assert!(
+            offset <= P::BLOCK_SIZE,
+            "Byte offset, considered during squeezing, must be <= {} -bytes",
+            P::BLOCK_SIZE
+        );
+
+        self.finalize_bits(domain_seperator, ds_bit_width, offset as u64);
+    }
+
+    /// Finalizes deck function state, same as `finalize_suffix` does, except that arbitrary many
+    /// ( say q ) leading output bytes are skipped i.e. first squeezed byte is q -th byte of
+    /// deck function output. Output mask is fast-forwarded, by rolling it once for each whole
+    /// block to be skipped, without applying permutation on them, and then byte offset is
+    /// positioned inside target block.
+    #[inline(always)]
+    pub fn finalize_skip(&mut self, suffix: DomainSuffix, skip: u64) {
+        if self.finalized == usize::MAX {
+            return;
+        }
+
+        let whole = suffix.width() / 8;
+        let bytes = suffix.bits().to_le_bytes();
+
+        self.absorb(&bytes[..whole]);
+        self.finalize_bits(
+            bytes.get(whole).copied().unwrap_or(0),
+            suffix.width() % 8,
+            skip,
+        );
+    }
+
+    /// Finalizes deck function state with a domain separator of bit width <= 7, merging it
+    /// with pending message bits ( if any ) and padding, while skipping `skip` -many leading
+    /// output bytes.
+    #[inline(always)]
+    fn finalize_bits(&mut self, domain_seperator: u8, ds_bit_width: usize, skip: u64) {
+        if self.finalized == usize::MAX {
+            return;
+        }
+
+        // pending message bits ( if any ) are followed by domain separator bits and padding
+        let mask = (1u8 << ds_bit_width) - 1u8;
+        let mut pad = self.pbyte as u16
+            | (((domain_seperator & mask) as u16) << self.pbits)
+            | (1u16 << (self.pbits + ds_bit_width));
+
+        if self.pbits + ds_bit_width >= 8 {
+            self.absorb_bytes(&[pad as u8]);
+            pad >>= 8;
+        }
+
+        self.pbyte = 0;
+        self.pbits = 0;
+
+        let iblk = self.iblk.as_mut();
+        iblk[self.ioff..].fill(0);
+        iblk[self.ioff] = pad as u8;
+
+        self.compress();
+        Rc::roll(&mut self.imask);
+
+        self.iblk = P::ZERO_BLOCK;
+        self.ioff = 0;
+        self.finalized = usize::MAX;
+
+        self.omask = self.acc;
+        P::permute(&mut self.omask);
+        self.fmask = self.omask;
+
+        let blk_cnt = skip / P::BLOCK_SIZE as u64;
+        Re::roll_n(&mut self.omask, blk_cnt);
+
+        self.expand();
+        self.oidx = blk_cnt;
+        self.ooff = (skip % P::BLOCK_SIZE as u64) as usize;
+    }
+
+    /// Finalizes deck function state, same as `finalize` does, except that domain suffix can be
+    /// multi-byte wide. Whole bytes of the suffix are absorbed as message bytes, while trailing
+    /// (<= 7) bits are merged with padding, so for suffixes of width <= 7, this routine is
+    /// equivalent to `finalize`.
+    #[inline(always)]
+    pub fn finalize_suffix(&mut self, suffix: DomainSuffix, offset: usize) {
+        debug_assert!(
+            offset <= P::BLOCK_SIZE,
+            "Byte offset, considered during squeezing, must be <= {} -bytes",
+            P::BLOCK_SIZE
+        );
+
+        self.finalize_skip(suffix, offset as u64);
+    }
+
+    /// Given that N -many message bytes are already absorbed into deck function state and
+    /// state is finalized, this routine can be used for squeezing arbitrary many bytes out
+    /// of deck function state. One can call this function arbitrary many times, each time
+    /// requesting arbitrary many bytes, if and only if state is already finalized and it's
+    /// not yet restarted for processing another message using `absorb->finalize->squeeze` cycle.
+    ///
+    /// This routine implements last portion of algorithm 1 of https://ia.cr/2016/1188.
+    #[inline(always)]
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        if self.finalized != usize::MAX {
+            return;
+        }
+
+        let mut off = 0;
+
+        while off < out.len() {
+            let read = cmp::min(P::BLOCK_SIZE - self.ooff, out.len() - off);
+            out[off..(off + read)]
+                .copy_from_slice(&self.oblk.as_ref()[self.ooff..(self.ooff + read)]);
+
+            self.ooff += read;
+            off += read;
+
+            if self.ooff == P::BLOCK_SIZE {
+                // whole blocks, following current one, are expanded right into output
+                let blk_cnt = (out.len() - off) / P::BLOCK_SIZE;
+                let body = &mut out[off..(off + blk_cnt * P::BLOCK_SIZE)];
+
+                Self::expand_blocks(&mut self.omask, &self.imask, body);
+                off += body.len();
+                self.oidx += blk_cnt as u64;
+
+                self.expand();
+                self.ooff = 0;
+                self.oidx += 1;
+            }
+        }
+    }
+
+    /// Given that deck function state is finalized, this routine returns byte position of next
+    /// byte to be squeezed, in deck function output stream, counting skipped bytes ( if any ).
+    #[inline(always)]
+    pub fn position(&self) -> u64 {
+        self.oidx * P::BLOCK_SIZE as u64 + self.ooff as u64
+    }
+
+    /// Given that deck function state is finalized, this routine seeks to arbitrary byte position
+    /// in deck function output stream, either forwards or backwards, so that next squeezed byte is
+    /// the one at that position. Seeking to block i costs at max i rolls of output mask, which is
+    /// recomputed from the one right after finalization when seeking backwards, but only a single
+    /// permutation call. Seeking within current block doesn't cost any permutation call.
+    ///
+    /// If the deck function state is not yet finalized, calling this routine does nothing.
+    #[inline(always)]
+    pub fn seek(&mut self, pos: u64) {
+        if self.finalized != usize::MAX {
+            return;
+        }
+
+        let blk_idx = pos / P::BLOCK_SIZE as u64;
+
+        if blk_idx != self.oidx {
+            // output mask is already rolled for block next to current one
+            let roll_cnt = if blk_idx > self.oidx {
+                blk_idx - self.oidx - 1
+            } else {
+                self.omask = self.fmask;
+                blk_idx
+            };
+
+            Re::roll_n(&mut self.omask, roll_cnt);
+
+            self.expand();
+            self.oidx = blk_idx;
+        }
+
+        self.ooff = (pos % P::BLOCK_SIZE as u64) as usize;
+    }
+
+    /// Given that a message of arbitrary byte length is absorbed into deck function state and
+    /// it's also finalized i.e. ready to be squeezed, this function can be invoked when you've
+    /// new message waiting to be absorbed into deck function state and you need to restart the
+    /// `absorb->finalize->squeeze` cycle.
+    ///
+    /// Note, if the deck function state is not yet finalized, calling this function should do nothing.
+    /// Remember you're very much allowed to restart `absorb->finalize->squeeze` cycle any number of times
+    /// you want.
+    ///
+    /// This routine implements portion of algorithm 1 of https://ia.cr/2016/1188.
+    #[inline(always)]
+    pub fn restart(&mut self) {
+        if self.finalized != usize::MAX {
+            return;
+        }
+
+        self.omask = P::ZERO_STATE;
+        self.fmask = P::ZERO_STATE;
+        self.oblk = P::ZERO_BLOCK;
+        self.ooff = 0;
+        self.oidx = 0;
+        self.finalized = usize::MIN;
+    }
+
+    /// Given that deck function state is not yet finalized and it's positioned at a message block
+    /// boundary ( i.e. absorbed message bytes of current string, if any, fill whole blocks ), this
+    /// routine returns an object, which can be used for absorbing n ( = `blk_cnt` ) whole message
+    /// blocks of current string out of order, identified by their index, relative to current
+    /// position. Returns error if the state is already finalized or not positioned at a block
+    /// boundary.
+    ///
+    /// As accumulator is XOR of P(block_i ^ roll_c^i(k)), for all block indices i, blocks can be
+    /// compressed in any order, see `IndexedAbsorber` for details.
+    #[inline(always)]
+    pub fn absorb_indexed(
+        &mut self,
+        blk_cnt: u64,
+    ) -> Result<IndexedAbsorber<'_, P, Rc, Re>, XoofffError> {
+        if self.finalized == usize::MAX || self.ioff != 0 || self.pbits != 0 {
+            return Err(XoofffError::WrongPhase);
+        }
+
+        Ok(IndexedAbsorber {
+            acc: P::ZERO_STATE,
+            blocks: BTreeSet::new(),
+            blk_cnt,
+            error: None,
+            last: None,
+            tail: P::ZERO_BLOCK,
+            tail_len: 0,
+            deck: self,
+        })
+    }
+
+    /// Given that deck function state is not yet finalized and it's positioned at a message block
+    /// boundary, this routine compresses a byte range of current string, starting at message
+    /// block index `start` ( relative to current position ), into a partial accumulator, without
+    /// modifying deck function state. Message bytes are split into whole blocks, while trailing
+    /// partial block ( if any ) is kept in partial accumulator, so only the range covering end of
+    /// the string is allowed to have byte length, which is not a multiple of BLOCK_SIZE.
+    ///
+    /// Input mask of first block is computed by rolling input mask `start` -many times, so that
+    /// ranges of a large message can be compressed independently ( say, on different machines ),
+    /// merged and finalized, see `PartialAccumulator`.
+    #[inline(always)]
+    pub fn accumulate(
+        &self,
+        start: u64,
+        msg: &[u8],
+    ) -> Result<PartialAccumulator<P, Rc, Re>, XoofffError> {
+        if self.finalized == usize::MAX || self.ioff != 0 || self.pbits != 0 {
+            return Err(XoofffError::WrongPhase);
+        }
+
+        let mut imask = self.imask;
+        Rc::roll_n(&mut imask, start);
+
+        let mut acc = P::ZERO_STATE;
+        let (body, rem) = msg.split_at(msg.len() - msg.len() % P::BLOCK_SIZE);
+        Self::compress_blocks(&mut imask, &mut acc, body);
+
+        let mut tail = P::ZERO_BLOCK;
+        tail.as_mut()[..rem.len()].copy_from_slice(rem);
+
+        Ok(PartialAccumulator {
+            acc,
+            start,
+            end: start + (msg.len() / P::BLOCK_SIZE) as u64,
+            tail,
+            tail_len: rem.len(),
+            _rolling: PhantomData,
+        })
+    }
+
+    /// Given that deck function state is not yet finalized and it's positioned at a message block
+    /// boundary, this routine merges partial accumulator, covering whole range of current string
+    /// ( i.e. starting at block index 0 ), into deck function state and finalizes it, producing
+    /// same output, as if whole string was absorbed sequentially, by calling `absorb`. Returns
+    /// error, leaving deck function state untouched, if partial accumulator doesn't start at
+    /// block index 0 or if the state isn't in right phase.
+    #[inline(always)]
+    pub fn finalize_accumulated(
+        &mut self,
+        partial: &PartialAccumulator<P, Rc, Re>,
+        suffix: DomainSuffix,
+        offset: usize,
+    ) -> Result<(), XoofffError> {
+        if offset > P::BLOCK_SIZE {
+            return Err(XoofffError::BadOffset(offset));
+        }
+        if self.finalized == usize::MAX || self.ioff != 0 || self.pbits != 0 {
+            return Err(XoofffError::WrongPhase);
+        }
+        if partial.start != 0 {
+            return Err(XoofffError::MissingBlock(0));
+        }
+
+        P::xor(&mut self.acc, &partial.acc);
+        Rc::roll_n(&mut self.imask, partial.end);
+
+        self.iblk = partial.tail;
+        self.ioff = partial.tail_len;
+        self.finalize_suffix(suffix, offset);
+
+        Ok(())
+    }
+
+    /// Fallible variant of `absorb`, which returns error, instead of silently dropping message
+    /// bytes, when deck function state is already finalized.
+    #[inline(always)]
+    pub fn try_absorb(&mut self, msg: &[u8]) -> Result<(), XoofffError> {
+        if self.finalized == usize::MAX {
+            return Err(XoofffError::WrongPhase);
+        }
+
+        self.absorb(msg);
+        Ok(())
+    }
+
+    /// Fallible variant of `finalize`, which returns error when domain separator bit width is
+    /// > 7, domain separator has bits set beyond its bit width, squeezing byte offset is
+    /// > BLOCK_SIZE or deck function state is already finalized.
+    #[inline(always)]
+    pub fn try_finalize(
+        &mut self,
+        domain_seperator: u8,
+        ds_bit_width: usize,
+        offset: usize,
+    ) -> Result<(), XoofffError> {
+        if ds_bit_width > 7 {
+            return Err(XoofffError::BadDomainSeparatorWidth(ds_bit_width));
+        }
+        let suffix = DomainSuffix::new(domain_seperator as u64, ds_bit_width)?;
+        if offset > P::BLOCK_SIZE {
+            return Err(XoofffError::BadOffset(offset));
+        }
+        if self.finalized == usize::MAX {
+            return Err(XoofffError::WrongPhase);
+        }
+
+        self.finalize_suffix(suffix, offset);
+        Ok(())
+    }
+
+    /// Fallible variant of `squeeze`, which returns error, instead of leaving output buffer
+    /// untouched, when deck function state is not yet finalized.
+    #[inline(always)]
+    pub fn try_squeeze(&mut self, out: &mut [u8]) -> Result<(), XoofffError> {
+        if self.finalized != usize::MAX {
+            return Err(XoofffError::WrongPhase);
+        }
+
+        self.squeeze(out);
+        Ok(())
+    }
+
+    /// Fallible variant of `seek`, which returns error when deck function state is not yet
+    /// finalized.
+    #[inline(always)]
+    pub fn try_seek(&mut self, pos: u64) -> Result<(), XoofffError> {
+        if self.finalized != usize::MAX {
+            return Err(XoofffError::WrongPhase);
+        }
+
+        self.seek(pos);
+        Ok(())
+    }
+
+    /// Compresses input message block ( buffer ) into accumulator, using current input mask,
+    /// which is rolled afterwards.
+    #[inline(always)]
+    fn compress(&mut self) {
+        let mut words = P::from_bytes(&self.iblk);
+        P::xor(&mut words, &self.imask);
+        P::permute(&mut words);
+        P::xor(&mut self.acc, &words);
+
+        Rc::roll(&mut self.imask);
+    }
+
+    /// Expands next output message block ( buffer ) from current output mask, which is
+    /// rolled afterwards.
+    #[inline(always)]
+    fn expand(&mut self) {
+        let mut words = self.omask;
+        P::permute(&mut words);
+        P::xor(&mut words, &self.imask);
+        P::to_bytes(&words, &mut self.oblk);
+
+        Re::roll(&mut self.omask);
+    }
+
+    /// Compresses whole message blocks into accumulator, starting with given input mask, which
+    /// is rolled once for each block, while permuting batches of independent blocks together.
+    #[inline(always)]
+    fn compress_blocks(imask: &mut P::State, acc: &mut P::State, msg: &[u8]) {
+        debug_assert!(
+            msg.len().is_multiple_of(P::BLOCK_SIZE),
+            "Message must consist of whole blocks"
+        );
+
+        let mut states = [P::ZERO_STATE; PERMUTE_BATCH];
+
+        for batch in msg.chunks(PERMUTE_BATCH * P::BLOCK_SIZE) {
+            let blk_cnt = batch.len() / P::BLOCK_SIZE;
+
+            for (state, chunk) in states.iter_mut().zip(batch.chunks_exact(P::BLOCK_SIZE)) {
+                let mut blk = P::ZERO_BLOCK;
+                blk.as_mut().copy_from_slice(chunk);
+
+                *state = P::from_bytes(&blk);
+                P::xor(state, imask);
+                Rc::roll(imask);
+            }
+
+            P::permute_many(&mut states[..blk_cnt]);
+            for state in &states[..blk_cnt] {
+                P::xor(acc, state);
+            }
+        }
+    }
+
+    /// Expands whole output blocks, starting with given output mask, which is rolled once for
+    /// each block, while permuting batches of independent blocks together.
+    #[inline(always)]
+    fn expand_blocks(omask: &mut P::State, imask: &P::State, out: &mut [u8]) {
+        let mut omasks = [P::ZERO_STATE; PERMUTE_BATCH];
+
+        for batch in out.chunks_mut(PERMUTE_BATCH * P::BLOCK_SIZE) {
+            let blk_cnt = batch.len() / P::BLOCK_SIZE;
+
+            for mask in &mut omasks[..blk_cnt] {
+                *mask = *omask;
+                Re::roll(omask);
+            }
+
+            Self::expand_masks(&omasks[..blk_cnt], imask, batch);
+        }
+    }
+
+    /// Expands whole output blocks, one from each of given (<= PERMUTE_BATCH) output masks,
+    /// permuting them together.
+    #[inline(always)]
+    fn expand_masks(omasks: &[P::State], imask: &P::State, out: &mut [u8]) {
+        debug_assert!(
+            omasks.len() <= PERMUTE_BATCH && out.len() == omasks.len() * P::BLOCK_SIZE,
+            "Output must consist of one whole block per output mask"
+        );
+
+        let mut states = [P::ZERO_STATE; PERMUTE_BATCH];
+        let states = &mut states[..omasks.len()];
+
+        states.copy_from_slice(omasks);
+        P::permute_many(states);
+
+        for (state, chunk) in states.iter_mut().zip(out.chunks_exact_mut(P::BLOCK_SIZE)) {
+            P::xor(state, imask);
+
+            let mut blk = P::ZERO_BLOCK;
+            P::to_bytes(state, &mut blk);
+            chunk.copy_from_slice(blk.as_ref());
+        }
+    }
+}
+
+/// Absorbs message blocks of a string into deck function state, out of order, so that parts of
+/// a large message, arriving in arbitrary order, can be absorbed as soon as they arrive.
+/// Message is split into n whole blocks of BLOCK_SIZE -bytes, each absorbed with its index, by
+/// calling `absorb_block_at`, and trailing partial block (< BLOCK_SIZE -bytes), absorbed by
+/// calling `absorb_tail`. Once all of them are absorbed, calling `finalize` validates that each
+/// of block indices 0, 1, .., n-1 is given exactly once and finalizes deck function state,
+/// producing same output, as if whole message was absorbed sequentially, by calling `absorb`.
+///
+/// Deck function state is left untouched until successful finalization.
+pub struct IndexedAbsorber<'a, P: Permutation, Rc, Re> {
+    deck: &'a mut Farfalle<P, Rc, Re>, // deck function, to be finalized
+    acc: P::State,                     // accumulator, of blocks absorbed so far
+    blocks: BTreeSet<u64>,             // indices of blocks absorbed so far
+    blk_cnt: u64,                      // number of blocks to be absorbed
+    error: Option<XoofffError>,        // first duplicate or out of range block ( if any )
+    last: Option<(u64, P::State)>,     // index and input mask of last absorbed block
+    tail: P::Block,                    // trailing partial block
+    tail_len: usize,                   // byte length of trailing partial block
+}
+
+impl<P, Rc, Re> IndexedAbsorber<'_, P, Rc, Re>
+where
+    P: Permutation,
+    Rc: Rolling<P::State>,
+    Re: Rolling<P::State>,
+{
+    /// Absorbs message block, of BLOCK_SIZE -bytes, at given index. Input mask of the block is
+    /// computed by rolling input mask, at start, index -many times, unless blocks arrive in
+    /// order, when rolling input mask of previous block, once, suffices.
+    #[inline(always)]
+    pub fn absorb_block_at(&mut self, index: u64, blk: &P::Block) {
+        if index >= self.blk_cnt {
+            self.error.get_or_insert(XoofffError::BadBlockIndex(index));
+            return;
+        }
+        if !self.blocks.insert(index) {
+            self.error.get_or_insert(XoofffError::DuplicateBlock(index));
+            return;
+        }
+
+        let mut imask = match self.last {
+            Some((last, mut imask)) if index == last + 1 => {
+                Rc::roll(&mut imask);
+                imask
+            }
+            Some((last, mut imask)) if index > last => {
+                Rc::roll_n(&mut imask, index - last);
+                imask
+            }
+            _ => {
+                let mut imask = self.deck.imask;
+                Rc::roll_n(&mut imask, index);
+                imask
+            }
+        };
+        self.last = Some((index, imask));
+
+        let words = P::from_bytes(blk);
+        P::xor(&mut imask, &words);
+        P::permute(&mut imask);
+        P::xor(&mut self.acc, &imask);
+    }
+
+    /// Absorbs trailing partial message block, of byte length < BLOCK_SIZE, which follows last
+    /// whole message block. Calling it again replaces previously given trailing partial block.
+    #[inline(always)]
+    pub fn absorb_tail(&mut self, msg: &[u8]) {
+        debug_assert!(
+            msg.len() < P::BLOCK_SIZE,
+            "Trailing partial block byte length must be < {}",
+            P::BLOCK_SIZE
+        );
+
+        self.tail = P::ZERO_BLOCK;
+        self.tail.as_mut()[..msg.len()].copy_from_slice(msg);
+        self.tail_len = msg.len();
+    }
+
+    /// Validates that each of block indices 0, 1, .., n-1 is absorbed exactly once and only then
+    /// merges absorbed blocks into deck function state, finalizing it, same as `finalize_suffix` does.
+    /// Otherwise error is returned, holding index of first duplicate, out of range or missing
+    /// block, while deck function state is left untouched.
+    #[inline(always)]
+    pub fn finalize(self, suffix: DomainSuffix, offset: usize) -> Result<(), XoofffError> {
+        if offset > P::BLOCK_SIZE {
+            return Err(XoofffError::BadOffset(offset));
+        }
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        if (self.blocks.len() as u64) < self.blk_cnt {
+            // all absorbed indices are unique and < n, so first missing one is first mismatch
+            let index = (0u64..)
+                .zip(self.blocks.iter())
+                .find(|(i, b)| i != *b)
+                .map_or(self.blocks.len() as u64, |(i, _)| i);
+
+            return Err(XoofffError::MissingBlock(index));
+        }
+
+        let deck = self.deck;
+
+        P::xor(&mut deck.acc, &self.acc);
+        Rc::roll_n(&mut deck.imask, self.blk_cnt);
+
+        deck.iblk = self.tail;
+        deck.ioff = self.tail_len;
+        deck.finalize_suffix(suffix, offset);
+
+        Ok(())
+    }
+}
+
+/// Partial accumulator, holding XOR of P(block_i ^ roll_c^i(k)), for all message blocks of a
+/// contiguous block index range \[start, end), along with trailing partial block ( if any ),
+/// which is produced by calling `Farfalle::accumulate`. Partial accumulators of adjacent ranges
+/// can be merged ( associatively ) into one, covering union of those ranges, and once a partial
+/// accumulator covers whole string, it can be finalized using `Farfalle::finalize_accumulated`.
+///
+/// It can be serialized, so that partial accumulators, computed on different machines, can be
+/// sent over wire, see `to_bytes` for serialization format.
+#[derive(Clone, Copy)]
+pub struct PartialAccumulator<P: Permutation, Rc, Re> {
+    acc: P::State,                      // accumulator, of blocks in range
+    start: u64,                         // index of first block in range
+    end: u64,                           // index of block, right after last one in range
+    tail: P::Block,                     // trailing partial block
+    tail_len: usize,                    // byte length of trailing partial block
+    _rolling: PhantomData<(P, Rc, Re)>, // permutation and rolling functions
+}
+
+impl<P, Rc, Re> PartialAccumulator<P, Rc, Re>
+where
+    P: Permutation,
+    Rc: Rolling<P::State>,
+    Re: Rolling<P::State>,
+{
+    /// Byte length of fixed size header of serialized partial accumulator
+    const HEADER_LEN: usize = 17;
+
+    /// Returns index range \[start, end) of message blocks, covered by this partial accumulator.
+    #[inline(always)]
+    pub fn range(&self) -> std::ops::Range<u64> {
+        self.start..self.end
+    }
+
+    /// Merges two partial accumulators, covering adjacent block index ranges, given in any order,
+    /// into one, covering union of those ranges. Returns error if ranges overlap, leave a gap
+    /// in between or if the one covering lower range has a trailing partial block i.e. it must
+    /// have been end of the string.
+    #[inline(always)]
+    pub fn merge(&self, other: &Self) -> Result<Self, XoofffError> {
+        let (lo, hi) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        if lo.end > hi.start {
+            return Err(XoofffError::DuplicateBlock(hi.start));
+        }
+        if lo.end < hi.start {
+            return Err(XoofffError::MissingBlock(lo.end));
+        }
+        if lo.tail_len != 0 {
+            return Err(XoofffError::TrailingPartialBlock(lo.end));
+        }
+
+        let mut acc = lo.acc;
+        P::xor(&mut acc, &hi.acc);
+
+        Ok(Self {
+            acc,
+            start: lo.start,
+            end: hi.end,
+            tail: hi.tail,
+            tail_len: hi.tail_len,
+            _rolling: PhantomData,
+        })
+    }
+
+    /// Serializes partial accumulator as start (8 -bytes) || end (8 -bytes) || byte length of
+    /// trailing partial block (1 -byte) || accumulator (BLOCK_SIZE -bytes) || trailing partial
+    /// block, s.t. integers are encoded in little endian byte order.
+    #[inline(always)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut acc = P::ZERO_BLOCK;
+        P::to_bytes(&self.acc, &mut acc);
+
+        let mut bytes = Vec::with_capacity(Self::HEADER_LEN + P::BLOCK_SIZE + self.tail_len);
+        bytes.extend_from_slice(&self.start.to_le_bytes());
+        bytes.extend_from_slice(&self.end.to_le_bytes());
+        bytes.push(self.tail_len as u8);
+        bytes.extend_from_slice(acc.as_ref());
+        bytes.extend_from_slice(&self.tail.as_ref()[..self.tail_len]);
+
+        bytes
+    }
+
+    /// Deserializes partial accumulator, serialized using `to_bytes`, returning error if it's
+    /// malformed.
+    #[inline(always)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, XoofffError> {
+        if bytes.len() < Self::HEADER_LEN + P::BLOCK_SIZE {
+            return Err(XoofffError::BadEncoding);
+        }
+
+        let (header, body) = bytes.split_at(Self::HEADER_LEN);
+
+        let start = u64::from_le_bytes(header[..8].try_into().unwrap());
+        let end = u64::from_le_bytes(header[8..16].try_into().unwrap());
+        let tail_len = header[16] as usize;
+
+        if start > end || tail_len >= P::BLOCK_SIZE || body.len() != P::BLOCK_SIZE + tail_len {
+            return Err(XoofffError::BadEncoding);
+        }
+
+        let mut acc = P::ZERO_BLOCK;
+        acc.as_mut().copy_from_slice(&body[..P::BLOCK_SIZE]);
+
+        let mut tail = P::ZERO_BLOCK;
+        tail.as_mut()[..tail_len].copy_from_slice(&body[P::BLOCK_SIZE..]);
+
+        Ok(Self {
+            acc: P::from_bytes(&acc),
+            start,
+            end,
+            tail,
+            tail_len,
+            _rolling: PhantomData,
+        })
+    }
+}
+
+/// Encodes integer x as left_encode(x), following section 2.3.1 of NIST SP 800-185 i.e. byte
+/// length of minimal big endian representation of x ( at least 1 -byte ) followed by that
+/// representation, returning encoded bytes along with how many of them are used.
+#[inline(always)]
+fn left_encode(x: u64) -> ([u8; 9], usize) {
+    let n = cmp::max(8 - (x.leading_zeros() as usize / 8), 1);
+
+    let mut enc = [0u8; 9];
+    enc[0] = n as u8;
+    enc[1..=n].copy_from_slice(&x.to_be_bytes()[8 - n..]);
+
+    (enc, n + 1)
+}
+
+/// Given a message of length N -bytes ( s.t. N < BLOCK_SIZE ), this routine pads the
+/// message following pad10* rule such that padded message length becomes BLOCK_SIZE -bytes.
+#[inline(always)]
+pub(crate) fn pad10x<P: Permutation>(msg: &[u8]) -> P::Block {
+    debug_assert!(
+        msg.len() < P::BLOCK_SIZE,
+        "Paddable message length must be < {}",
+        P::BLOCK_SIZE
+    );
+
+    let mlen = msg.len();
+    let mut res = P::ZERO_BLOCK;
+
+    res.as_mut()[..mlen].copy_from_slice(msg);
+    res.as_mut()[mlen] = 0x01;
+
+    res
+}
@@ -0,0 +1,130 @@
+use super::{Farfalle, PERMUTE_BATCH, Permutation, Rolling};
+use rayon::prelude::*;
+use std::cmp;
+
+/// Minimum number of message blocks, compressed by a single task, so that cost of jumping input
+/// mask ahead and spawning the task is amortized
+const MIN_TASK_BLOCKS: usize = 64;
+
+/// Number of output blocks, whose output masks are computed sequentially, before expanding them
+/// in parallel, so that memory required for holding output masks stays bounded
+const BATCH_BLOCKS: usize = 4096;
+
+impl<P, Rc, Re> Farfalle<P, Rc, Re>
+where
+    P: Permutation + Sync,
+    P::State: Send + Sync,
+    P::Block: Send + Sync,
+    Rc: Rolling<P::State> + Sync,
+    Re: Rolling<P::State> + Sync,
+{
+    /// Given a message M of byte length N (>=0), this routine absorbs it into the state of the
+    /// deck function, same as `absorb` does, while compressing message blocks in parallel, on
+    /// rayon thread pool. Message is split into ranges of whole blocks, each compressed by a
+    /// task, starting with input mask jumped ahead to start of its range, and accumulators of
+    /// all ranges are XOR-ed into accumulator of the deck function. Resulting state is bit
+    /// identical to the one obtained by calling `absorb`.
+    ///
+    /// Note, pending bits of a partial message byte ( if any, see `absorb_bits` ) make message
+    /// unaligned, in which case this routine falls back to `absorb`.
+    ///
+    /// Each task permutes batches of its message blocks together, same as `absorb` does, so with
+    /// `simd` feature enabled, `Xoofff` compresses them using SIMD, on each worker thread.
+    pub fn absorb_par(&mut self, msg: &[u8]) {
+        if self.finalized == usize::MAX {
+            return;
+        }
+
+        if self.pbits != 0 {
+            self.absorb(msg);
+            return;
+        }
+
+        // fill partially filled input message block ( if any ), so that remaining message
+        // starts at a block boundary
+        let head = cmp::min(msg.len(), (P::BLOCK_SIZE - self.ioff) % P::BLOCK_SIZE);
+        self.absorb(&msg[..head]);
+
+        let msg = &msg[head..];
+        let blk_cnt = msg.len() / P::BLOCK_SIZE;
+        let (body, tail) = msg.split_at(blk_cnt * P::BLOCK_SIZE);
+
+        let task_cnt = rayon::current_num_threads() * 4;
+        let task_blks = cmp::max(blk_cnt.div_ceil(task_cnt), MIN_TASK_BLOCKS);
+
+        let deck = &*self;
+        let acc = body
+            .par_chunks(task_blks * P::BLOCK_SIZE)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let start = (i * task_blks) as u64;
+                // deck function is positioned at block boundary, not yet finalized
+                deck.accumulate(start, chunk).unwrap().acc
+            })
+            .reduce(
+                || P::ZERO_STATE,
+                |mut a, b| {
+                    P::xor(&mut a, &b);
+                    a
+                },
+            );
+
+        P::xor(&mut self.acc, &acc);
+        Rc::roll_n(&mut self.imask, blk_cnt as u64);
+
+        self.absorb(tail);
+    }
+
+    /// Given that deck function state is finalized, this routine squeezes arbitrary many bytes
+    /// out of it, same as `squeeze` does, while expanding output blocks in parallel, on rayon
+    /// thread pool. Output masks of a batch of blocks are computed sequentially, by rolling
+    /// output mask ( which is cheap ), and then batches of them are permuted together ( same as
+    /// `squeeze` does, i.e. using SIMD, with `simd` feature enabled, for `Xoofff` ), in parallel.
+    /// Squeezed bytes are identical to the ones obtained by calling `squeeze` and the state is
+    /// left positioned right after them, for further squeezing.
+    pub fn squeeze_par(&mut self, out: &mut [u8]) {
+        if self.finalized != usize::MAX {
+            return;
+        }
+
+        // drain current output block, so that remaining output starts at a block boundary
+        let head = cmp::min(out.len(), (P::BLOCK_SIZE - self.ooff) % P::BLOCK_SIZE);
+        self.squeeze(&mut out[..head]);
+
+        let out = &mut out[head..];
+        let blk_cnt = out.len() / P::BLOCK_SIZE;
+
+        if blk_cnt == 0 {
+            self.squeeze(out);
+            return;
+        }
+
+        // current output block is already expanded, while output mask is for the next one
+        let (first, rest) = out.split_at_mut(P::BLOCK_SIZE);
+        first.copy_from_slice(self.oblk.as_ref());
+
+        let (body, tail) = rest.split_at_mut((blk_cnt - 1) * P::BLOCK_SIZE);
+        let imask = self.imask;
+
+        let mut omasks = Vec::with_capacity(cmp::min(blk_cnt - 1, BATCH_BLOCKS));
+        for batch in body.chunks_mut(BATCH_BLOCKS * P::BLOCK_SIZE) {
+            omasks.clear();
+            for _ in 0..(batch.len() / P::BLOCK_SIZE) {
+                omasks.push(self.omask);
+                Re::roll(&mut self.omask);
+            }
+
+            batch
+                .par_chunks_mut(PERMUTE_BATCH * P::BLOCK_SIZE)
+                .zip(omasks.par_chunks(PERMUTE_BATCH))
+                .for_each(|(chunk, omasks)| Self::expand_masks(omasks, &imask, chunk));
+        }
+
+        // expand block, right after squeezed whole blocks, so that state is positioned at its start
+        self.expand();
+        self.oidx += blk_cnt as u64;
+        self.ooff = 0;
+
+        self.squeeze(tail);
+    }
+}
@@ -0,0 +1,167 @@
+use crunchy::unroll;
+
+/// Maximum number of rounds one can request to have when applying Keccak-p\[1600, n_r\] permutation i.e. n_r <= MAX_ROUNDS
+///
+/// See section 3.3 of https://doi.org/10.6028/NIST.FIPS.202
+const MAX_ROUNDS: usize = 24;
+
+/// Keccak-p\[1600, n_r\] round constants, taken from table 1 of https://keccak.team/keccakp.html
+const RC: [u64; MAX_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation offsets of ρ step mapping, indexed by lane position x + 5 * y, see table 2 of https://doi.org/10.6028/NIST.FIPS.202
+const ROT: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// θ step mapping of Keccak-p permutation, as described in algorithm 1 of https://doi.org/10.6028/NIST.FIPS.202.
+#[inline(always)]
+fn theta(state: &mut [u64; 25]) {
+    let mut c = [0u64; 5];
+    unroll! {
+        for x in 0..5 {
+            c[x] = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+    }
+
+    let mut d = [0u64; 5];
+    unroll! {
+        for x in 0..5 {
+            d[x] = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+        }
+    }
+
+    unroll! {
+        for i in 0..25 {
+            state[i] ^= d[i % 5];
+        }
+    }
+}
+
+/// Combined ρ and π step mappings of Keccak-p permutation, as described in algorithm 2 and 3 of
+/// https://doi.org/10.6028/NIST.FIPS.202 i.e. lane at (x, y) is rotated and moved to (y, 2x + 3y).
+#[inline(always)]
+fn rho_pi(state: &mut [u64; 25]) {
+    let src = *state;
+    unroll! {
+        for i in 0..25 {
+            let x = i % 5;
+            let y = i / 5;
+            state[y + 5 * ((2 * x + 3 * y) % 5)] = src[i].rotate_left(ROT[i]);
+        }
+    }
+}
+
+/// χ step mapping of Keccak-p permutation, as described in algorithm 4 of https://doi.org/10.6028/NIST.FIPS.202.
+#[inline(always)]
+fn chi(state: &mut [u64; 25]) {
+    unroll! {
+        for y in 0..5 {
+            let off = y * 5;
+            let mut plane = [0u64; 5];
+            plane.copy_from_slice(&state[off..off + 5]);
+
+            for x in 0..5 {
+                state[off + x] = plane[x] ^ (!plane[(x + 1) % 5] & plane[(x + 2) % 5]);
+            }
+        }
+    }
+}
+
+/// ι step mapping of Keccak-p permutation, as described in algorithm 6 of https://doi.org/10.6028/NIST.FIPS.202.
+#[inline(always)]
+fn iota(state: &mut [u64; 25], ridx: usize) {
+    state[0] ^= RC[ridx];
+}
+
+/// Round function of Keccak-p permutation, as described in section 3.3 of https://doi.org/10.6028/NIST.FIPS.202.
+#[inline(always)]
+fn round(state: &mut [u64; 25], ridx: usize) {
+    debug_assert!(ridx < MAX_ROUNDS, "Round index must ∈ [0, MAX_ROUNDS) !");
+
+    theta(state);
+    rho_pi(state);
+    chi(state);
+    iota(state, ridx);
+}
+
+/// Keccak-p\[1600, n_r\] permutation function s.t. last n_r ( <= MAX_ROUNDS ) rounds of
+/// Keccak-f\[1600\] are applied on permutation state, as described in algorithm 7 of
+/// https://doi.org/10.6028/NIST.FIPS.202.
+#[inline(always)]
+pub fn permute<const ROUNDS: usize>(state: &mut [u64; 25]) {
+    debug_assert!(
+        ROUNDS <= MAX_ROUNDS,
+        "Requested rounds must be < MAX_ROUNDS !"
+    );
+
+    let start = MAX_ROUNDS - ROUNDS;
+    for ridx in start..MAX_ROUNDS {
+        round(state, ridx);
+    }
+}
+
+/// Byte length of rate part of Keccak\[c = 512\] sponge, underlying SHA3-256
+const SHA3_256_RATE: usize = 136;
+
+/// Byte length of SHA3-256 digest
+pub const SHA3_256_DIGEST_LEN: usize = 32;
+
+/// Given a message of byte length N (>=0), computes its 32 -bytes SHA3-256 digest, using sponge
+/// construction, instantiated with Keccak-f\[1600\] ( i.e. Keccak-p\[1600, 24\] ) permutation,
+/// where message is appended with 01 -bits, before pad10*1 rule is applied, as described in
+/// section 6.1 of https://doi.org/10.6028/NIST.FIPS.202.
+#[inline(always)]
+pub fn sha3_256(msg: &[u8]) -> [u8; SHA3_256_DIGEST_LEN] {
+    let mut state = [0u64; 25];
+
+    let absorb = |state: &mut [u64; 25], blk: &[u8; SHA3_256_RATE]| {
+        for (lane, word) in state.iter_mut().zip(blk.chunks_exact(8)) {
+            *lane ^= u64::from_le_bytes(word.try_into().unwrap());
+        }
+        permute::<MAX_ROUNDS>(state);
+    };
+
+    let mut blocks = msg.chunks_exact(SHA3_256_RATE);
+    for chunk in blocks.by_ref() {
+        absorb(&mut state, chunk.try_into().unwrap());
+    }
+
+    let rem = blocks.remainder();
+    let mut blk = [0u8; SHA3_256_RATE];
+
+    blk[..rem.len()].copy_from_slice(rem);
+    blk[rem.len()] ^= 0x06;
+    blk[SHA3_256_RATE - 1] ^= 0x80;
+    absorb(&mut state, &blk);
+
+    let mut dig = [0u8; SHA3_256_DIGEST_LEN];
+    for (chunk, lane) in dig.chunks_exact_mut(8).zip(state.iter()) {
+        chunk.copy_from_slice(&lane.to_le_bytes());
+    }
+    dig
+}
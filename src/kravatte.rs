@@ -0,0 +1,91 @@
+use crate::farfalle::{Farfalle, FarfalleKey, PartialAccumulator, Permutation};
+use crate::keccak;
+use crate::rolling::{RollKc, RollKe};
+use crunchy::unroll;
+
+/// Keccak-p\[1600, n_r\] being a 1600 -bit permutation, messages are consumed in 200 -bytes chunks
+const BLOCK_SIZE: usize = 200;
+
+/// \# -of rounds for Keccak-p permutation, see https://ia.cr/2016/1188
+const ROUNDS: usize = 6;
+
+/// \# -of lanes ( each of 64 -bit width ) in Keccak-p\[1600\] permutation state
+const LANE_CNT: usize = BLOCK_SIZE / std::mem::size_of::<u64>();
+
+/// Keccak-p\[1600, n_r\] permutation, applied on 25 lanes ( each of 64 -bit width ), s.t. message
+/// blocks are mapped to permutation state by interpreting each eight consecutive bytes as a
+/// little endian u64 word.
+#[derive(Clone, Copy)]
+pub struct KeccakP1600<const ROUNDS: usize>;
+
+impl<const ROUNDS: usize> Permutation for KeccakP1600<ROUNDS> {
+    const BLOCK_SIZE: usize = BLOCK_SIZE;
+
+    type State = [u64; LANE_CNT];
+    type Block = [u8; BLOCK_SIZE];
+
+    const ZERO_STATE: Self::State = [0u64; LANE_CNT];
+    const ZERO_BLOCK: Self::Block = [0u8; BLOCK_SIZE];
+
+    #[inline(always)]
+    fn permute(state: &mut Self::State) {
+        keccak::permute::<ROUNDS>(state);
+    }
+
+    #[inline(always)]
+    fn from_bytes(block: &Self::Block) -> Self::State {
+        let mut words = [0u64; LANE_CNT];
+
+        debug_assert_eq!(LANE_CNT, 25);
+        unroll! {
+            for i in 0..25 {
+                words[i] = u64::from_le_bytes(block[i * 8..(i + 1) * 8].try_into().unwrap());
+            }
+        }
+        words
+    }
+
+    #[inline(always)]
+    fn to_bytes(state: &Self::State, block: &mut Self::Block) {
+        debug_assert_eq!(LANE_CNT, 25);
+        unroll! {
+            for i in 0..25 {
+                block[i * 8..(i + 1) * 8].copy_from_slice(&state[i].to_le_bytes());
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn xor(dst: &mut Self::State, src: &Self::State) {
+        debug_assert_eq!(LANE_CNT, 25);
+        unroll! {
+            for i in 0..25 {
+                dst[i] ^= src[i];
+            }
+        }
+    }
+
+    /// SHA3-256, built on Keccak-f\[1600\] permutation.
+    #[inline(always)]
+    fn digest(msg: &[u8]) -> [u8; 32] {
+        keccak::sha3_256(msg)
+    }
+}
+
+/// Kravatte is a deck function, obtained by instantiating Farfalle construction with
+/// Keccak-p\[1600, 6\] permutation and two rolling functions, offering exactly same
+/// incremental `absorb->finalize->squeeze` ( and `restart` ) API as Xoofff does, while
+/// consuming messages and producing output in 200 -bytes chunks. Key can be of arbitrary
+/// byte length and byte offset, considered during squeezing, must be <= 200.
+///
+/// See https://ia.cr/2016/1188 for definition of Kravatte, with non-linear output mask
+/// rolling function, as updated in Farfalle paper, published in ToSC 2017.
+pub type Kravatte = Farfalle<KeccakP1600<ROUNDS>, RollKc, RollKe>;
+
+/// Masked key of Kravatte, which is derived only once, so that arbitrary many Kravatte deck
+/// functions can be instantiated from it, without applying Keccak-p\[1600, 6\] permutation again.
+pub type KravatteKey = FarfalleKey<KeccakP1600<ROUNDS>, RollKc, RollKe>;
+
+/// Partial accumulator of Kravatte, covering a range of message blocks, which can be computed
+/// independently, merged with the ones covering adjacent ranges and finalized.
+pub type KravattePartialAccumulator = PartialAccumulator<KeccakP1600<ROUNDS>, RollKc, RollKe>;
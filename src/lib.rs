@@ -1,23 +1,41 @@
 #![cfg_attr(feature = "simd", feature(portable_simd))]
 
-mod rolling;
+mod error;
+pub mod farfalle;
+pub mod rolling;
 
 #[cfg(feature = "dev")]
 pub mod xoodoo;
 #[cfg(not(feature = "dev"))]
 mod xoodoo;
 
+#[cfg(feature = "dev")]
+pub mod keccak;
+#[cfg(not(feature = "dev"))]
+mod keccak;
+
 #[allow(unused)]
 mod xoofff;
 
-#[cfg(not(feature = "simd"))]
-pub use crate::xoofff::Xoofff;
+mod kravatte;
+pub use crate::kravatte::{Kravatte, KravatteKey, KravattePartialAccumulator};
+
+pub use crate::error::XoofffError;
+pub use crate::farfalle::DomainSuffix;
+
+pub use crate::xoofff::{Xoofff, XoofffKey, XoofffPartialAccumulator};
 
 #[cfg(feature = "simd")]
 mod simd;
 
-#[cfg(feature = "simd")]
-pub use crate::simd::Xoofff;
+pub mod bo;
+pub mod deck;
+pub mod sane;
+pub mod sanse;
+pub mod sponge;
+pub mod wbc;
+pub mod wbcae;
+pub mod xoodyak;
 
 #[cfg(test)]
 mod tests;
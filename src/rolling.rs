@@ -1,13 +1,48 @@
+use super::farfalle::Rolling;
 use super::xoodoo::cyclic_shift;
+use std::sync::OnceLock;
+
+/// Xoodoo permutation state, as a vector over GF(2)^384, packed into 64 -bit words s.t. bit z
+/// of lane i lives at bit index 32 * i + z
+type Vector = [u64; 6];
+
+/// Linear map over GF(2)^384, represented by its 384 columns i.e. images of basis vectors
+type Matrix = [Vector; 384];
+
+/// Matrix powers M^(2^i) for i ∈ [0, 64), M being matrix representation of roll_Xc, which
+/// are computed lazily, only once, when `roll_xc_n` is first invoked
+static ROLL_XC_POWERS: OnceLock<Vec<Matrix>> = OnceLock::new();
+
+/// Input mask rolling function roll_Xc of Xoofff, see `roll_xc`
+#[derive(Clone, Copy)]
+pub struct RollXc;
+
+/// Output mask rolling function roll_Xe of Xoofff, see `roll_xe`
+#[derive(Clone, Copy)]
+pub struct RollXe;
+
+impl Rolling<[u32; 12]> for RollXc {
+    #[inline(always)]
+    fn roll(state: &mut [u32; 12]) {
+        roll_xc(state);
+    }
+
+    #[inline(always)]
+    fn roll_n(state: &mut [u32; 12], n: u64) {
+        roll_xc_n(state, n);
+    }
+}
+
+impl Rolling<[u32; 12]> for RollXe {
+    #[inline(always)]
+    fn roll(state: &mut [u32; 12]) {
+        roll_xe(state);
+    }
+}
 
 /// Input mask rolling function roll_Xc, updating the Xoodoo permutation state, as
 /// described in section 3 of https://ia.cr/2018/767
-pub fn roll_xc(state: &mut [u32]) {
-    debug_assert!(
-        state.len() == 12,
-        "Xoodoo permutation state must have 12 lanes !"
-    );
-
+pub fn roll_xc(state: &mut [u32; 12]) {
     state[0] ^= (state[0] << 13) ^ state[4].rotate_left(3);
     let b = cyclic_shift::<3, 0>(&state[..4]);
 
@@ -15,14 +50,92 @@ pub fn roll_xc(state: &mut [u32]) {
     state[8..12].copy_from_slice(&b);
 }
 
+/// Applies input mask rolling function roll_Xc n times, on Xoodoo permutation state, in
+/// O(log n) time. As roll_Xc is a linear map M over GF(2)^384, applying it n times is same as
+/// multiplying state with M^n, which is computed as product of precomputed matrix powers
+/// M^(2^i), for each set bit i of n.
+pub fn roll_xc_n(state: &mut [u32; 12], n: u64) {
+    let powers = ROLL_XC_POWERS.get_or_init(roll_xc_powers);
+
+    let mut vector = to_vector(state);
+    for (i, power) in powers.iter().enumerate() {
+        if (n >> i) & 1 == 1 {
+            vector = mul(power, &vector);
+        }
+    }
+
+    from_vector(&vector, state);
+}
+
+/// Computes matrix powers M^(2^i) for i ∈ [0, 64), M being matrix representation of roll_Xc.
+fn roll_xc_powers() -> Vec<Matrix> {
+    let mut powers = Vec::with_capacity(64);
+
+    let mut matrix = [[0u64; 6]; 384];
+    for (j, col) in matrix.iter_mut().enumerate() {
+        let mut basis = [0u32; 12];
+        basis[j / 32] = 1u32 << (j % 32);
+
+        roll_xc(&mut basis);
+        *col = to_vector(&basis);
+    }
+    powers.push(matrix);
+
+    for i in 1..64 {
+        let prev = &powers[i - 1];
+
+        let mut matrix = [[0u64; 6]; 384];
+        for (col, prev_col) in matrix.iter_mut().zip(prev.iter()) {
+            *col = mul(prev, prev_col);
+        }
+        powers.push(matrix);
+    }
+
+    powers
+}
+
+/// Multiplies matrix with vector, over GF(2).
+#[inline(always)]
+fn mul(matrix: &Matrix, vector: &Vector) -> Vector {
+    let mut res = [0u64; 6];
+
+    for (i, &word) in vector.iter().enumerate() {
+        let mut word = word;
+        while word != 0 {
+            let col = &matrix[i * 64 + word.trailing_zeros() as usize];
+            for (r, c) in res.iter_mut().zip(col.iter()) {
+                *r ^= *c;
+            }
+
+            word &= word - 1;
+        }
+    }
+
+    res
+}
+
+/// Packs Xoodoo permutation state into a vector over GF(2)^384.
+#[inline(always)]
+fn to_vector(state: &[u32; 12]) -> Vector {
+    let mut vector = [0u64; 6];
+    for (i, v) in vector.iter_mut().enumerate() {
+        *v = (state[2 * i] as u64) | ((state[2 * i + 1] as u64) << 32);
+    }
+    vector
+}
+
+/// Unpacks a vector over GF(2)^384 into Xoodoo permutation state.
+#[inline(always)]
+fn from_vector(vector: &Vector, state: &mut [u32; 12]) {
+    for (i, &v) in vector.iter().enumerate() {
+        state[2 * i] = v as u32;
+        state[2 * i + 1] = (v >> 32) as u32;
+    }
+}
+
 /// State rolling function roll_Xe, updating Xoodoo permutation state, as described
 /// in section 3 of https://ia.cr/2018/767
-pub fn roll_xe(state: &mut [u32]) {
-    debug_assert!(
-        state.len() == 12,
-        "Xoodoo permutation state must have 12 lanes !"
-    );
-
+pub fn roll_xe(state: &mut [u32; 12]) {
     let tmp = state[4] & state[8];
     state[0] = tmp ^ state[0].rotate_left(5) ^ state[4].rotate_left(13) ^ 0x00000007u32;
     let b = cyclic_shift::<3, 0>(&state[..4]);
@@ -30,3 +143,49 @@ pub fn roll_xe(state: &mut [u32]) {
     state.copy_within(4..12, 0);
     state[8..12].copy_from_slice(&b);
 }
+
+/// Input mask rolling function roll_Kc of Kravatte, see `roll_kc`
+#[derive(Clone, Copy)]
+pub struct RollKc;
+
+/// Output mask rolling function roll_Ke of Kravatte, see `roll_ke`
+#[derive(Clone, Copy)]
+pub struct RollKe;
+
+impl Rolling<[u64; 25]> for RollKc {
+    #[inline(always)]
+    fn roll(state: &mut [u64; 25]) {
+        roll_kc(state);
+    }
+}
+
+impl Rolling<[u64; 25]> for RollKe {
+    #[inline(always)]
+    fn roll(state: &mut [u64; 25]) {
+        roll_ke(state);
+    }
+}
+
+/// Input mask rolling function roll_c of Kravatte, which is a linear feedback shift register,
+/// operating on the last plane ( i.e. five lanes x_0, .., x_4, each of 64 -bit width ) of
+/// Keccak-p\[1600\] permutation state, as described in https://ia.cr/2016/1188
+///
+/// x_0 <- (x_0 <<< 7) ^ x_1 ^ (x_1 >> 3), followed by (x_0, .., x_4) <- (x_1, .., x_4, x_0)
+pub fn roll_kc(state: &mut [u64; 25]) {
+    let x0 = state[20].rotate_left(7) ^ state[21] ^ (state[21] >> 3);
+
+    state.copy_within(21..25, 20);
+    state[24] = x0;
+}
+
+/// Output mask rolling function roll_e of Kravatte, which is a non-linear feedback shift
+/// register, operating on the last two planes ( i.e. ten lanes x_0, .., x_9, each of 64 -bit
+/// width ) of Keccak-p\[1600\] permutation state, as described in https://ia.cr/2016/1188
+///
+/// x_0 <- (x_0 <<< 7) ^ (x_1 <<< 18) ^ (x_2 & (x_1 >> 1)), followed by (x_0, .., x_9) <- (x_1, .., x_9, x_0)
+pub fn roll_ke(state: &mut [u64; 25]) {
+    let x0 = state[15].rotate_left(7) ^ state[16].rotate_left(18) ^ (state[17] & (state[16] >> 1));
+
+    state.copy_within(16..25, 15);
+    state[24] = x0;
+}
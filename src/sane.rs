@@ -0,0 +1,148 @@
+use crate::farfalle::DomainSuffix;
+use crate::xoofff::{ct_eq, Xoofff};
+
+/// Byte length of authentication tag produced by Xoofff-SANE, which is also the byte offset
+/// of keystream, see https://ia.cr/2018/767
+pub const TAG_LEN: usize = 16;
+
+/// Xoofff-SANE is a session authenticated encryption scheme, obtained by instantiating
+/// Deck-SANE mode with Xoofff deck function. A session is started with a nonce and then
+/// arbitrary many messages, each with its own associated data, can be wrapped ( or unwrapped ).
+/// Each produced tag authenticates the whole session history upto and including the latest
+/// message, so reordering, dropping or replaying messages is detected by the receiver.
+///
+/// Session history is never re-absorbed, rather it's kept in the accumulator of underlying
+/// deck function, by restarting its `absorb->finalize->squeeze` cycle after each string.
+///
+/// See algorithm 3 of https://ia.cr/2016/1188 for definition of Farfalle-SANE and
+/// https://ia.cr/2018/767 for Xoofff-SANE.
+#[derive(Clone, Copy)]
+pub struct XoofffSane {
+    deck: Xoofff, // deck function, holding session history
+    e: u8,        // alternating bit, flipped after each wrap/ unwrap call
+}
+
+impl XoofffSane {
+    /// Create a new instance of Xoofff-SANE session, with a key of arbitrary byte length and
+    /// a nonce of arbitrary byte length, returning session object along with initial tag,
+    /// which must be sent to receiver, so that it can validate the session startup.
+    #[inline(always)]
+    pub fn new(key: &[u8], nonce: &[u8]) -> (Self, [u8; TAG_LEN]) {
+        let mut deck = Xoofff::new(key);
+        let mut tag = [0u8; TAG_LEN];
+
+        // history <- N, T <- 0^t + F_K(history)
+        deck.absorb(nonce);
+        deck.finalize_suffix(DomainSuffix::EMPTY, 0);
+        deck.squeeze(&mut tag);
+        deck.restart();
+
+        (Self { deck, e: 0 }, tag)
+    }
+
+    /// Given that receiver has started a session using same key and nonce, as the sender did,
+    /// this routine can be used for verifying initial tag, returning session object only if tag is valid.
+    #[inline(always)]
+    pub fn start(key: &[u8], nonce: &[u8], tag: &[u8; TAG_LEN]) -> Option<Self> {
+        let (session, computed) = Self::new(key, nonce);
+        if ct_eq(&computed, tag) {
+            Some(session)
+        } else {
+            None
+        }
+    }
+
+    /// Given associated data A and plaintext P, both of arbitrary byte length, this routine
+    /// encrypts P into C s.t. |C| = |P| and computes authentication tag T over whole session
+    /// history, updated with A and C. Note, ciphertext slice must be as long as plaintext.
+    ///
+    /// This routine implements wrap operation of algorithm 3 of https://ia.cr/2016/1188.
+    #[inline(always)]
+    pub fn wrap(&mut self, ad: &[u8], pt: &[u8], ct: &mut [u8]) -> [u8; TAG_LEN] {
+        debug_assert_eq!(
+            pt.len(),
+            ct.len(),
+            "Plaintext and ciphertext must be of same byte length"
+        );
+
+        if !pt.is_empty() {
+            self.keystream(ct);
+            for (c, p) in ct.iter_mut().zip(pt.iter()) {
+                *c ^= *p;
+            }
+        }
+
+        let tag = self.authenticate(ad, ct);
+        self.e ^= 1;
+        tag
+    }
+
+    /// Given associated data A, ciphertext C and authentication tag T, this routine decrypts
+    /// C into P s.t. |P| = |C|, then recomputes tag over whole session history, updated with
+    /// A and C, and compares it with T in constant-time. Returns truth value if tag matches,
+    /// otherwise plaintext slice is zeroed and false is returned. Note, session history is
+    /// updated irrespective of outcome, so a session must be aborted on failed unwrap.
+    ///
+    /// This routine implements unwrap operation of algorithm 3 of https://ia.cr/2016/1188.
+    #[inline(always)]
+    pub fn unwrap(&mut self, ad: &[u8], ct: &[u8], tag: &[u8; TAG_LEN], pt: &mut [u8]) -> bool {
+        debug_assert_eq!(
+            ct.len(),
+            pt.len(),
+            "Ciphertext and plaintext must be of same byte length"
+        );
+
+        if !ct.is_empty() {
+            self.keystream(pt);
+            for (p, c) in pt.iter_mut().zip(ct.iter()) {
+                *p ^= *c;
+            }
+        }
+
+        let computed = self.authenticate(ad, ct);
+        self.e ^= 1;
+
+        let flg = ct_eq(&computed, tag);
+        if !flg {
+            pt.fill(0);
+        }
+        flg
+    }
+
+    /// Squeezes keystream F_K(e ◦ history) << t, into output slice, without touching session history.
+    #[inline(always)]
+    fn keystream(&self, out: &mut [u8]) {
+        let mut deck = self.deck;
+        let suffix = DomainSuffix::from_bits(&[self.e == 1]).unwrap();
+        deck.finalize_suffix(suffix, TAG_LEN);
+        deck.squeeze(out);
+    }
+
+    /// Updates session history with A || 0 || e ( if needed ) and C || 1 || e ( if needed ),
+    /// returning tag T = 0^t + F_K(history).
+    #[inline(always)]
+    fn authenticate(&mut self, ad: &[u8], ct: &[u8]) -> [u8; TAG_LEN] {
+        let mut tag = [0u8; TAG_LEN];
+
+        if !ad.is_empty() || ct.is_empty() {
+            self.deck.absorb(ad);
+            let suffix = DomainSuffix::from_bits(&[false, self.e == 1]).unwrap();
+            self.deck.finalize_suffix(suffix, 0);
+
+            if ct.is_empty() {
+                self.deck.squeeze(&mut tag);
+            }
+            self.deck.restart();
+        }
+
+        if !ct.is_empty() {
+            self.deck.absorb(ct);
+            let suffix = DomainSuffix::from_bits(&[true, self.e == 1]).unwrap();
+            self.deck.finalize_suffix(suffix, 0);
+            self.deck.squeeze(&mut tag);
+            self.deck.restart();
+        }
+
+        tag
+    }
+}
@@ -0,0 +1,140 @@
+use crate::farfalle::DomainSuffix;
+use crate::xoofff::{ct_eq, Xoofff};
+
+/// Byte length of authentication tag produced by Xoofff-SANSE, which also serves as synthetic IV
+pub const TAG_LEN: usize = 32;
+
+/// Xoofff-SANSE is a nonce-misuse resistant session authenticated encryption scheme, obtained
+/// by instantiating Deck-SANSE mode with Xoofff deck function. It follows SIV approach i.e.
+/// authentication tag is first computed over associated data and plaintext, which is then used
+/// as synthetic IV for deriving keystream. No nonce is required, though repeating same session
+/// history, associated data and plaintext reveals that, which is inherent to any deterministic scheme.
+///
+/// Similar to Xoofff-SANE, each tag authenticates the whole session history upto and including
+/// the latest message, which is kept in the accumulator of underlying deck function, by restarting
+/// its `absorb->finalize->squeeze` cycle after each string.
+///
+/// See https://ia.cr/2018/767 for definition of Xoofff-SANSE.
+#[derive(Clone, Copy)]
+pub struct XoofffSanse {
+    deck: Xoofff, // deck function, holding session history
+    e: u8,        // alternating bit, flipped after each wrap/ unwrap call
+}
+
+impl XoofffSanse {
+    /// Create a new instance of Xoofff-SANSE session, with a key of arbitrary byte length,
+    /// starting with empty session history.
+    #[inline(always)]
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            deck: Xoofff::new(key),
+            e: 0,
+        }
+    }
+
+    /// Given associated data A and plaintext P, both of arbitrary byte length, this routine
+    /// computes authentication tag T over whole session history, updated with A and P, and
+    /// encrypts P into C s.t. |C| = |P|, using keystream derived from T. Note, ciphertext slice
+    /// must be as long as plaintext.
+    #[inline(always)]
+    pub fn wrap(&mut self, ad: &[u8], pt: &[u8], ct: &mut [u8]) -> [u8; TAG_LEN] {
+        debug_assert_eq!(
+            pt.len(),
+            ct.len(),
+            "Plaintext and ciphertext must be of same byte length"
+        );
+
+        let mut tag = [0u8; TAG_LEN];
+
+        self.absorb_ad(ad, pt.is_empty(), &mut tag);
+
+        if !pt.is_empty() {
+            // keystream is derived from history, not yet updated with plaintext
+            let deck = self.deck;
+
+            // history <- P || 01 || e ◦ history, T <- 0^t + F_K(history)
+            self.deck.absorb(pt);
+            let suffix = DomainSuffix::from_bits(&[false, true, self.e == 1]).unwrap();
+            self.deck.finalize_suffix(suffix, 0);
+            self.deck.squeeze(&mut tag);
+            self.deck.restart();
+
+            keystream(deck, self.e, &tag, ct);
+            for (c, p) in ct.iter_mut().zip(pt.iter()) {
+                *c ^= *p;
+            }
+        }
+
+        self.e ^= 1;
+        tag
+    }
+
+    /// Given associated data A, ciphertext C and authentication tag T, this routine decrypts
+    /// C into P s.t. |P| = |C|, using keystream derived from T, then recomputes tag over whole
+    /// session history, updated with A and P, and compares it with T in constant-time. Returns
+    /// truth value if tag matches, otherwise plaintext slice is zeroed and false is returned.
+    /// Note, session history is updated irrespective of outcome, so a session must be aborted
+    /// on failed unwrap.
+    #[inline(always)]
+    pub fn unwrap(&mut self, ad: &[u8], ct: &[u8], tag: &[u8; TAG_LEN], pt: &mut [u8]) -> bool {
+        debug_assert_eq!(
+            ct.len(),
+            pt.len(),
+            "Ciphertext and plaintext must be of same byte length"
+        );
+
+        let mut computed = [0u8; TAG_LEN];
+
+        self.absorb_ad(ad, ct.is_empty(), &mut computed);
+
+        if !ct.is_empty() {
+            keystream(self.deck, self.e, tag, pt);
+            for (p, c) in pt.iter_mut().zip(ct.iter()) {
+                *p ^= *c;
+            }
+
+            // history <- P || 01 || e ◦ history, T' <- 0^t + F_K(history)
+            self.deck.absorb(pt);
+            let suffix = DomainSuffix::from_bits(&[false, true, self.e == 1]).unwrap();
+            self.deck.finalize_suffix(suffix, 0);
+            self.deck.squeeze(&mut computed);
+            self.deck.restart();
+        }
+
+        self.e ^= 1;
+
+        let flg = ct_eq(&computed, tag);
+        if !flg {
+            pt.fill(0);
+        }
+        flg
+    }
+
+    /// Updates session history with A || 0 || e, if A is non-empty or there's no plaintext.
+    /// In later case, tag T = 0^t + F_K(history) is also squeezed.
+    #[inline(always)]
+    fn absorb_ad(&mut self, ad: &[u8], no_msg: bool, tag: &mut [u8; TAG_LEN]) {
+        if ad.is_empty() && !no_msg {
+            return;
+        }
+
+        self.deck.absorb(ad);
+        let suffix = DomainSuffix::from_bits(&[false, self.e == 1]).unwrap();
+        self.deck.finalize_suffix(suffix, 0);
+
+        if no_msg {
+            self.deck.squeeze(tag);
+        }
+        self.deck.restart();
+    }
+}
+
+/// Given a copy of deck function, holding session history, squeezes keystream
+/// F_K(T || 11 || e ◦ history) into output slice.
+#[inline(always)]
+fn keystream(mut deck: Xoofff, e: u8, tag: &[u8; TAG_LEN], out: &mut [u8]) {
+    deck.absorb(tag);
+    let suffix = DomainSuffix::from_bits(&[true, true, e == 1]).unwrap();
+    deck.finalize_suffix(suffix, 0);
+    deck.squeeze(out);
+}
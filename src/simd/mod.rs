@@ -1,39 +1,47 @@
+use core::simd::Simd;
+
 mod xoodoo;
 
-// target_arch || target_feature || x   || runtime detection possible
-// wasm32      || simd128        || x4  || no, make two modules
-// x86/x86_64  || avx2           || x8  || yes
-// x86/x86_64  || avx512         || x16 || yes
-
-#[cfg(feature = "dev")]
-pub use xoodoo::permutex;
-
-#[cfg(all(
-    any(target_arch = "x86", target_arch = "x86_64"),
-    target_feature = "avx512"
-))]
-mod x16;
-
-#[cfg(all(
-    any(target_arch = "x86", target_arch = "x86_64"),
-    target_feature = "avx512"
-))]
-pub use x16::Xoofff;
-
-#[cfg(all(
-    any(target_arch = "x86", target_arch = "x86_64"),
-    target_feature = "avx2"
-))]
-mod x8;
-
-#[cfg(all(
-    any(target_arch = "x86", target_arch = "x86_64"),
-    target_feature = "avx2"
-))]
-pub use x8::Xoofff;
-
-#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
-mod x4;
-
-#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
-pub use x4::Xoofff;
+// target_feature || # -of states permuted together
+// avx512f        || 16
+// avx2           || 8
+// otherwise      || 4 ( e.g. SSE2, NEON or wasm32 simd128 )
+
+/// \# -of Xoodoo permutation states, permuted together, using SIMD lanes of 32 -bit width
+#[cfg(target_feature = "avx512f")]
+const LANES: usize = 16;
+
+/// \# -of Xoodoo permutation states, permuted together, using SIMD lanes of 32 -bit width
+#[cfg(all(target_feature = "avx2", not(target_feature = "avx512f")))]
+const LANES: usize = 8;
+
+/// \# -of Xoodoo permutation states, permuted together, using SIMD lanes of 32 -bit width
+#[cfg(not(any(target_feature = "avx2", target_feature = "avx512f")))]
+const LANES: usize = 4;
+
+/// Applies Xoodoo\[n_r\] permutation on many independent states, LANES of them at a time, by
+/// transposing them into SIMD vectors s.t. lane j of i -th vector holds i -th word of j -th state.
+/// Trailing states, which don't fill all SIMD lanes, are permuted one after another.
+#[inline]
+pub(crate) fn permute_many<const ROUNDS: usize>(states: &mut [[u32; 12]]) {
+    let mut chunks = states.chunks_exact_mut(LANES);
+
+    for chunk in chunks.by_ref() {
+        let mut statex = [Simd::<u32, LANES>::splat(0u32); 12];
+        for (i, word) in statex.iter_mut().enumerate() {
+            *word = Simd::from_array(core::array::from_fn(|j| chunk[j][i]));
+        }
+
+        xoodoo::permutex::<LANES, ROUNDS>(&mut statex);
+
+        for (i, word) in statex.iter().enumerate() {
+            for (state, lane) in chunk.iter_mut().zip(word.to_array()) {
+                state[i] = lane;
+            }
+        }
+    }
+
+    for state in chunks.into_remainder() {
+        crate::xoodoo::permute::<ROUNDS>(state);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::xoodoo;
+use crate::xoofff::{bytes_to_le_words, words_to_le_bytes};
+use std::cmp;
+
+/// Xoodoo\[n_r\] being a 384 -bit permutation, sponge/ duplex state is 48 -bytes wide
+const STATE_SIZE: usize = 48;
+
+/// Padding rule, applied on last ( possibly empty ) message block, after domain separator bits
+/// are appended, so that it fills whole rate part of the state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Padding {
+    /// pad10* rule i.e. a single 1 -bit, followed by minimum number of 0 -bits
+    Pad10x,
+    /// pad10*1 rule i.e. a single 1 -bit, followed by minimum number of 0 -bits and a final 1 -bit
+    /// at the end of rate part, which is needed for multi-rate security of sponge construction
+    Pad10x1,
+}
+
+/// Given byte offset into rate part of state, this routine adds domain separator bits ( at max
+/// 7 -bits wide, interpreted in little endian order ) along with padding into state.
+#[inline(always)]
+fn pad<const RATE: usize>(
+    state: &mut [u8; STATE_SIZE],
+    off: usize,
+    padding: Padding,
+    domain_seperator: u8,
+    ds_bit_width: usize,
+) {
+    debug_assert!(
+        ds_bit_width <= 7,
+        "Domain seperator bit width is not allowed to be > 7"
+    );
+    debug_assert!(
+        padding == Padding::Pad10x || ds_bit_width < 7,
+        "Domain seperator bit width is not allowed to be > 6, with pad10*1 rule"
+    );
+
+    let mask = (1u8 << ds_bit_width) - 1u8;
+    state[off] ^= (1u8 << ds_bit_width) | (domain_seperator & mask);
+
+    if padding == Padding::Pad10x1 {
+        state[RATE - 1] ^= 0x80;
+    }
+}
+
+/// Applies Xoodoo\[n_r\] permutation on state, represented as bytes, s.t. each four consecutive
+/// bytes are interpreted as a little endian u32 word.
+#[inline(always)]
+fn permute<const ROUNDS: usize>(state: &mut [u8; STATE_SIZE]) {
+    let mut words = bytes_to_le_words(state);
+    xoodoo::permute::<ROUNDS>(&mut words);
+    words_to_le_bytes(&words, state);
+}
+
+/// Sponge construction, instantiated with Xoodoo\[n_r\] permutation s.t. rate r ( in bytes )
+/// is configurable and capacity is 48 - r -bytes, offering extendable output function. Domain
+/// separator bits ( if any ) are appended to message, before padding rule is applied.
+///
+/// Message can be absorbed by calling `absorb` routine arbitrary many times, then state is
+/// finalized and arbitrary many bytes can be squeezed by calling `squeeze` routine arbitrary
+/// many times. Attempting to absorb after finalization or squeezing before it, does nothing.
+///
+/// See https://keccak.team/files/CSF-0.1.pdf for definition of sponge construction.
+#[derive(Clone, Copy)]
+pub struct XoodooSponge<const ROUNDS: usize, const RATE: usize> {
+    state: [u8; STATE_SIZE], // Xoodoo permutation state, as bytes
+    padding: Padding,        // padding rule, applied on last message block
+    off: usize,              // offset into rate part of state
+    finalized: bool,         // is sponge state finalized ?
+}
+
+impl<const ROUNDS: usize, const RATE: usize> XoodooSponge<ROUNDS, RATE> {
+    /// Create a new instance of Xoodoo sponge, with all zero initial state and given padding rule.
+    #[inline(always)]
+    pub fn new(padding: Padding) -> Self {
+        debug_assert!(
+            RATE > 0 && RATE < STATE_SIZE,
+            "Rate must ∈ [1, {}) -bytes",
+            STATE_SIZE
+        );
+
+        Self {
+            state: [0u8; STATE_SIZE],
+            padding,
+            off: 0,
+            finalized: false,
+        }
+    }
+
+    /// Given a message of byte length N (>=0), absorbs it into rate part of state, applying
+    /// permutation whenever rate part is full.
+    #[inline(always)]
+    pub fn absorb(&mut self, msg: &[u8]) {
+        if self.finalized {
+            return;
+        }
+
+        let mut off = 0;
+        while off < msg.len() {
+            let read = cmp::min(RATE - self.off, msg.len() - off);
+
+            let dst = &mut self.state[self.off..(self.off + read)];
+            for (s, m) in dst.iter_mut().zip(msg[off..(off + read)].iter()) {
+                *s ^= *m;
+            }
+
+            self.off += read;
+            off += read;
+
+            if self.off == RATE {
+                permute::<ROUNDS>(&mut self.state);
+                self.off = 0;
+            }
+        }
+    }
+
+    /// Finalizes sponge state, by appending domain separator bits ( at max 7 -bits wide ) and
+    /// padding to absorbed message, so that arbitrary many bytes can be squeezed.
+    #[inline(always)]
+    pub fn finalize(&mut self, domain_seperator: u8, ds_bit_width: usize) {
+        if self.finalized {
+            return;
+        }
+
+        pad::<RATE>(
+            &mut self.state,
+            self.off,
+            self.padding,
+            domain_seperator,
+            ds_bit_width,
+        );
+        permute::<ROUNDS>(&mut self.state);
+
+        self.off = 0;
+        self.finalized = true;
+    }
+
+    /// Given that sponge state is finalized, squeezes arbitrary many bytes out of it.
+    #[inline(always)]
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        if !self.finalized {
+            return;
+        }
+
+        let mut off = 0;
+        while off < out.len() {
+            if self.off == RATE {
+                permute::<ROUNDS>(&mut self.state);
+                self.off = 0;
+            }
+
+            let read = cmp::min(RATE - self.off, out.len() - off);
+            out[off..(off + read)].copy_from_slice(&self.state[self.off..(self.off + read)]);
+
+            self.off += read;
+            off += read;
+        }
+    }
+}
+
+/// Duplex construction, instantiated with Xoodoo\[n_r\] permutation s.t. rate r ( in bytes )
+/// is configurable and capacity is 48 - r -bytes. Each duplexing call absorbs an input block
+/// ( of byte length < r ), appended with domain separator bits and padding, applies permutation
+/// and returns at max r -bytes output, which makes it suitable for building keyed sponges,
+/// lightweight PRNGs, MACs and authenticated encryption schemes.
+///
+/// See https://ia.cr/2011/499 for definition of duplex construction.
+#[derive(Clone, Copy)]
+pub struct XoodooDuplex<const ROUNDS: usize, const RATE: usize> {
+    state: [u8; STATE_SIZE], // Xoodoo permutation state, as bytes
+    padding: Padding,        // padding rule, applied on each input block
+}
+
+impl<const ROUNDS: usize, const RATE: usize> XoodooDuplex<ROUNDS, RATE> {
+    /// Create a new instance of Xoodoo duplex, with all zero initial state and given padding rule.
+    #[inline(always)]
+    pub fn new(padding: Padding) -> Self {
+        debug_assert!(
+            RATE > 0 && RATE < STATE_SIZE,
+            "Rate must ∈ [1, {}) -bytes",
+            STATE_SIZE
+        );
+
+        Self {
+            state: [0u8; STATE_SIZE],
+            padding,
+        }
+    }
+
+    /// Duplexing call, absorbing input block σ ( s.t. |σ| < r ) appended with domain separator
+    /// bits ( at max 7 -bits wide ) and padding, applying permutation and then squeezing output
+    /// block Z ( s.t. |Z| <= r ).
+    #[inline(always)]
+    pub fn duplex(
+        &mut self,
+        input: &[u8],
+        domain_seperator: u8,
+        ds_bit_width: usize,
+        out: &mut [u8],
+    ) {
+        debug_assert!(
+            input.len() < RATE,
+            "Input block byte length must be < {}",
+            RATE
+        );
+        debug_assert!(
+            out.len() <= RATE,
+            "Output block byte length must be <= {}",
+            RATE
+        );
+
+        for (s, i) in self.state.iter_mut().zip(input.iter()) {
+            *s ^= *i;
+        }
+
+        pad::<RATE>(
+            &mut self.state,
+            input.len(),
+            self.padding,
+            domain_seperator,
+            ds_bit_width,
+        );
+        permute::<ROUNDS>(&mut self.state);
+
+        out.copy_from_slice(&self.state[..out.len()]);
+    }
+}
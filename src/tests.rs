@@ -1,3 +1,4 @@
+use crate::sponge::Padding;
 use crate::Xoofff;
 use rand::{thread_rng, RngCore};
 use std::cmp;
@@ -57,6 +58,52 @@ fn test_xoofff_kat() {
     }
 }
 
+/// Test that permuting many independent Xoodoo\[6\] states together ( using SIMD, when `simd`
+/// feature is enabled ) is same as permuting them one after another.
+#[test_case(1; "states = 1")]
+#[test_case(4; "states = 4")]
+#[test_case(7; "states = 7")]
+#[test_case(16; "states = 16")]
+#[test_case(37; "states = 37")]
+fn test_xoodoo_permute_many(cnt: usize) {
+    use crate::farfalle::Permutation;
+    use crate::xoofff::Xoodoo;
+
+    let mut rng = thread_rng();
+
+    let mut states0 = vec![[0u32; 12]; cnt];
+    states0
+        .iter_mut()
+        .for_each(|state| state.iter_mut().for_each(|w| *w = rng.next_u32()));
+    let mut states1 = states0.clone();
+
+    states0.iter_mut().for_each(Xoodoo::<6>::permute);
+    Xoodoo::<6>::permute_many(&mut states1);
+
+    assert_eq!(states0, states1);
+}
+
+/// Test functional correctness of Keccak-p\[1600, 6\] permutation, used in Kravatte, by
+/// comparing it with Keccak-p\[1600, n_r\] implementation of https://crates.io/crates/keccak,
+/// on random permutation states.
+#[test]
+fn test_keccak_p1600_6() {
+    use crate::keccak;
+
+    let mut rng = thread_rng();
+
+    for _ in 0..64 {
+        let mut state0 = [0u64; 25];
+        state0.iter_mut().for_each(|lane| *lane = rng.next_u64());
+        let mut state1 = state0;
+
+        keccak::permute::<6>(&mut state0);
+        ::keccak::p1600(&mut state1, 6);
+
+        assert_eq!(state0, state1);
+    }
+}
+
 #[test_case(32, 0, 32, 0b1, 1, 0; "key = 32B message = 0B digest = 32B offset = 0B")]
 #[test_case(16, 32, 64, 0b11, 2, 0; "key = 16B message = 32B digest = 64B offset = 0B")]
 #[test_case(32, 64, 128, 0b101, 3, 1; "key = 32B message = 64B digest = 128B offset = 1B")]
@@ -65,7 +112,8 @@ fn test_xoofff_kat() {
 #[test_case(32, 512, 1024, 0b10101, 5, 8; "key = 32B message = 512B digest = 1024B offset = 8B")]
 #[test_case(32, 1024, 2048, 0, 0, 16; "key = 32B message = 1024B digest = 2048B offset = 16B")]
 #[test_case(47, 2048, 4096, 0b1, 2, 16; "key = 47B message = 1024B digest = 4096B offset = 16B")]
-#[test_case(48, 1024, 32, 0, 0, 32 => panics "Key byte length must be < 48")]
+#[test_case(48, 1024, 32, 0, 0, 32; "key = 48B message = 1024B digest = 32B offset = 32B")]
+#[test_case(64, 1024, 32, 0, 0, 32; "key = 64B message = 1024B digest = 32B offset = 32B")]
 #[test_case(24, 1024, 32, 0, 0, 49 => panics "Byte offset, considered during squeezing, must be <= 48 -bytes")]
 fn test_xoofff_incremental_io(
     klen: usize,
@@ -121,3 +169,1595 @@ fn test_xoofff_incremental_io(
 
     assert_eq!(dig0, dig1);
 }
+
+/// Test that fallible deck function API reports misuse as error, instead of panicking or
+/// silently ignoring the request, while producing same output as infallible API on success.
+#[test]
+fn test_xoofff_fallible_api() {
+    use crate::XoofffError;
+
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut msg = [0u8; 64];
+    let mut dig0 = [0u8; 32];
+    let mut dig1 = [0u8; 32];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut msg);
+
+    let mut deck0 = Xoofff::new(&key);
+    deck0.absorb(&msg);
+    deck0.finalize(0b1, 1, 8);
+    deck0.squeeze(&mut dig0);
+
+    let mut deck1 = Xoofff::new(&key);
+    assert_eq!(deck1.try_squeeze(&mut dig1), Err(XoofffError::WrongPhase));
+    assert_eq!(dig1, [0u8; 32]);
+
+    deck1.try_absorb(&msg).unwrap();
+    assert_eq!(
+        deck1.try_finalize(0b1, 8, 8),
+        Err(XoofffError::BadDomainSeparatorWidth(8))
+    );
+    assert_eq!(
+        deck1.try_finalize(0b101, 2, 8),
+        Err(XoofffError::BadDomainSeparatorBits(0b101))
+    );
+    assert_eq!(
+        deck1.try_finalize(0b1, 1, 49),
+        Err(XoofffError::BadOffset(49))
+    );
+    deck1.try_finalize(0b1, 1, 8).unwrap();
+
+    assert_eq!(deck1.try_absorb(&msg), Err(XoofffError::WrongPhase));
+    assert_eq!(deck1.try_finalize(0b1, 1, 8), Err(XoofffError::WrongPhase));
+
+    deck1.try_squeeze(&mut dig1).unwrap();
+    assert_eq!(dig0, dig1);
+}
+
+/// Test that typestate deck function API produces same output bytes as `Xoofff`, for a
+/// sequence of messages, processed by restarting `absorb->finalize->squeeze` cycle.
+#[test_case(32, &[0, 1, 47, 48, 49, 200], 64; "key = 32B digest = 64B")]
+#[test_case(47, &[1024, 0, 3], 4096; "key = 47B digest = 4096B")]
+fn test_xoofff_typestate_deck(klen: usize, mlens: &[usize], dlen: usize) {
+    use crate::deck::Deck;
+    use crate::DomainSuffix;
+
+    let mut rng = thread_rng();
+
+    let mut key = vec![0u8; klen];
+    rng.fill_bytes(&mut key);
+
+    let mut deck0 = Xoofff::new(&key);
+    let mut deck1 = Deck::new(&key);
+
+    for &mlen in mlens {
+        let mut msg = vec![0u8; mlen];
+        let mut dig0 = vec![0u8; dlen];
+        let mut dig1 = vec![0u8; dlen];
+
+        rng.fill_bytes(&mut msg);
+
+        deck0.absorb(&msg);
+        deck0.finalize(0b1, 1, 0);
+        deck0.squeeze(&mut dig0);
+        deck0.restart();
+
+        deck1.absorb(&msg);
+        let mut squeezing = deck1.finalize(DomainSuffix::new(0b1, 1).unwrap(), 0);
+        squeezing.squeeze(&mut dig1[..dlen / 2]);
+        squeezing.squeeze(&mut dig1[dlen / 2..]);
+        deck1 = squeezing.restart();
+
+        assert_eq!(dig0, dig1);
+    }
+}
+
+/// Test that domain suffix validates its bits against bit width and that finalizing with a
+/// multi-byte suffix is same as absorbing its whole bytes, followed by finalizing with its
+/// trailing bits.
+#[test_case(&[], 0, 0; "suffix = 0b")]
+#[test_case(&[true, false, true], 0b101, 0; "suffix = 3b")]
+#[test_case(&[false; 8], 0, 1; "suffix = 8b")]
+#[test_case(&[true; 13], 0x1fff, 1; "suffix = 13b")]
+#[test_case(&[true; 64], u64::MAX, 8; "suffix = 64b")]
+fn test_xoofff_domain_suffix(bits: &[bool], word: u64, whole: usize) {
+    use crate::{DomainSuffix, XoofffError};
+
+    let suffix = DomainSuffix::from_bits(bits).unwrap();
+    assert_eq!(suffix, DomainSuffix::new(word, bits.len()).unwrap());
+    assert_eq!(
+        DomainSuffix::new(word | (1 << (bits.len() % 64)), bits.len() % 64),
+        Err(XoofffError::BadDomainSeparatorBits(
+            word | (1 << (bits.len() % 64))
+        ))
+    );
+    assert_eq!(
+        DomainSuffix::new(0, 65),
+        Err(XoofffError::BadDomainSeparatorWidth(65))
+    );
+
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut msg = [0u8; 47];
+    let mut dig0 = [0u8; 64];
+    let mut dig1 = [0u8; 64];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut msg);
+
+    let bytes = word.to_le_bytes();
+
+    let mut deck0 = Xoofff::new(&key);
+    deck0.absorb(&msg);
+    deck0.absorb(&bytes[..whole]);
+    deck0.finalize(
+        bytes.get(whole).copied().unwrap_or(0),
+        bits.len() - whole * 8,
+        0,
+    );
+    deck0.squeeze(&mut dig0);
+
+    let mut deck1 = Xoofff::new(&key);
+    deck1.absorb(&msg);
+    deck1.finalize_suffix(suffix, 0);
+    deck1.squeeze(&mut dig1);
+
+    assert_eq!(dig0, dig1);
+}
+
+/// Test that skipping q leading bytes of deck function output, during finalization, is same as
+/// squeezing and discarding them.
+#[test_case(0; "skip = 0B")]
+#[test_case(1; "skip = 1B")]
+#[test_case(47; "skip = 47B")]
+#[test_case(48; "skip = 48B")]
+#[test_case(49; "skip = 49B")]
+#[test_case(96; "skip = 96B")]
+#[test_case(1000; "skip = 1000B")]
+fn test_xoofff_finalize_skip(skip: u64) {
+    use crate::DomainSuffix;
+
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut msg = [0u8; 100];
+    let mut out = vec![0u8; skip as usize + 100];
+    let mut dig = [0u8; 100];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut msg);
+
+    let mut deck0 = Xoofff::new(&key);
+    deck0.absorb(&msg);
+    deck0.finalize(0b11, 2, 0);
+    deck0.squeeze(&mut out);
+
+    let mut deck1 = Xoofff::new(&key);
+    deck1.absorb(&msg);
+    deck1.finalize_skip(DomainSuffix::new(0b11, 2).unwrap(), skip);
+    deck1.squeeze(&mut dig[..13]);
+    deck1.squeeze(&mut dig[13..]);
+
+    assert_eq!(&out[skip as usize..], &dig[..]);
+}
+
+/// Test that seeking forwards and backwards, within squeezed deck function output stream,
+/// results in squeezing same bytes as found at that position, in output squeezed sequentially.
+#[test_case(0, &[0, 1, 47, 48, 200, 49, 1000, 0, 95, 96]; "skip = 0B")]
+#[test_case(100, &[100, 0, 47, 1023, 512, 48, 3, 2000]; "skip = 100B")]
+fn test_xoofff_seek(skip: u64, positions: &[u64]) {
+    use crate::DomainSuffix;
+
+    const DLEN: usize = 4096;
+    const SLEN: usize = 50;
+
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut msg = [0u8; 64];
+    let mut out = vec![0u8; DLEN];
+    let mut dig = [0u8; SLEN];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut msg);
+
+    let mut deck0 = Xoofff::new(&key);
+    deck0.absorb(&msg);
+    deck0.finalize(0, 0, 0);
+    deck0.squeeze(&mut out);
+
+    let mut deck1 = Xoofff::new(&key);
+    assert_eq!(deck1.try_seek(0), Err(crate::XoofffError::WrongPhase));
+
+    deck1.absorb(&msg);
+    deck1.finalize_skip(DomainSuffix::EMPTY, skip);
+    assert_eq!(deck1.position(), skip);
+
+    for &pos in positions {
+        deck1.seek(pos);
+        assert_eq!(deck1.position(), pos);
+
+        deck1.squeeze(&mut dig);
+        assert_eq!(deck1.position(), pos + SLEN as u64);
+
+        let pos = pos as usize;
+        assert_eq!(&out[pos..(pos + SLEN)], &dig[..]);
+    }
+}
+
+/// Test that absorbing a bit string, in pieces of arbitrary bit length, followed by finalization
+/// is same as absorbing whole bytes of the bit string, appended with domain separator bits, and
+/// finalizing with trailing (< 8) bits as domain separator.
+#[test_case(0, &[], 0b1, 1; "message = 0b")]
+#[test_case(3, &[3], 0b10, 2; "message = 3b")]
+#[test_case(13, &[5, 8], 0b1011, 4; "message = 13b")]
+#[test_case(384, &[1, 383], 0b111, 3; "message = 384b")]
+#[test_case(389, &[7, 7, 7, 368], 0b1010101, 7; "message = 389b")]
+#[test_case(1021, &[1, 1020], 0, 0; "message = 1021b")]
+#[test_case(4099, &[387, 0, 3, 1, 3708], 0b110, 3; "message = 4099b")]
+fn test_xoofff_absorb_bits(
+    mlen: usize,
+    pieces: &[usize],
+    domain_seperator: u8,
+    ds_bit_width: usize,
+) {
+    /// Packs sequence of bits into bytes, first bit being least significant one of first byte.
+    fn pack(bits: &[bool]) -> Vec<u8> {
+        let mut bytes = vec![0u8; bits.len().div_ceil(8)];
+        for (i, &b) in bits.iter().enumerate() {
+            bytes[i / 8] |= (b as u8) << (i % 8);
+        }
+        bytes
+    }
+
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut msg = vec![0u8; mlen.div_ceil(8)];
+    let mut dig0 = [0u8; 64];
+    let mut dig1 = [0u8; 64];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut msg);
+
+    let bits = (0..mlen)
+        .map(|i| (msg[i / 8] >> (i % 8)) & 1 == 1)
+        .collect::<Vec<bool>>();
+
+    // whole bytes of message bits || domain separator bits, followed by trailing bits
+    let mut suffixed = bits.clone();
+    suffixed.extend((0..ds_bit_width).map(|i| (domain_seperator >> i) & 1 == 1));
+
+    let whole = suffixed.len() / 8;
+    let packed = pack(&suffixed);
+
+    let mut deck0 = Xoofff::new(&key);
+    deck0.absorb(&packed[..whole]);
+    deck0.finalize(
+        packed.get(whole).copied().unwrap_or(0),
+        suffixed.len() % 8,
+        0,
+    );
+    deck0.squeeze(&mut dig0);
+
+    // message bits, absorbed in pieces
+    let mut deck1 = Xoofff::new(&key);
+
+    let mut off = 0;
+    for &plen in pieces {
+        let piece = &bits[off..(off + plen)];
+        deck1.absorb_bits(&pack(piece), plen);
+        off += plen;
+    }
+    assert_eq!(off, mlen);
+
+    deck1.finalize(domain_seperator, ds_bit_width, 0);
+    deck1.squeeze(&mut dig1);
+
+    assert_eq!(dig0, dig1);
+
+    // whole bytes of message, absorbed through byte oriented API, are same as bits
+    if mlen == msg.len() * 8 {
+        let mut deck2 = Xoofff::new(&key);
+        deck2.absorb(&msg);
+        deck2.finalize(domain_seperator, ds_bit_width, 0);
+        deck2.squeeze(&mut dig1);
+
+        assert_eq!(dig0, dig1);
+    }
+}
+
+/// Test that tuples of messages are encoded injectively, while being absorbed, by comparing
+/// against known encoding and ensuring that tuples with same concatenation result in different
+/// deck function output.
+#[test_case(&[b"ab", b"c"], &[b"a", b"bc"]; "(ab, c) vs (a, bc)")]
+#[test_case(&[b"", b"abc"], &[b"abc", b""]; "(, abc) vs (abc, )")]
+#[test_case(&[b"abc"], &[b"abc", b""]; "(abc) vs (abc, )")]
+#[test_case(&[&[0u8; 32]], &[&[0u8; 31], &[0u8; 1]]; "(0^32) vs (0^31, 0)")]
+fn test_xoofff_absorb_tuple(tuple0: &[&[u8]], tuple1: &[&[u8]]) {
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut dig0 = [0u8; 32];
+    let mut dig1 = [0u8; 32];
+    let mut dig2 = [0u8; 32];
+
+    rng.fill_bytes(&mut key);
+
+    let mut deck0 = Xoofff::new(&key);
+    deck0.absorb_tuple(tuple0);
+    deck0.finalize(0, 0, 0);
+    deck0.squeeze(&mut dig0);
+
+    let mut deck1 = Xoofff::new(&key);
+    deck1.absorb_tuple(tuple1);
+    deck1.finalize(0, 0, 0);
+    deck1.squeeze(&mut dig1);
+
+    assert_ne!(dig0, dig1);
+
+    // left_encode(bit length) || message, for each message of the tuple
+    let mut deck2 = Xoofff::new(&key);
+    for msg in tuple0 {
+        let blen = msg.len() * 8;
+        if blen < 256 {
+            deck2.absorb(&[1, blen as u8]);
+        } else {
+            deck2.absorb(&[2, (blen >> 8) as u8, blen as u8]);
+        }
+        deck2.absorb(msg);
+    }
+    deck2.finalize(0, 0, 0);
+    deck2.squeeze(&mut dig2);
+
+    assert_eq!(dig0, dig2);
+}
+
+/// Test that customized deck function is different from non-customized one, from the one which
+/// absorbed encoded customization string as first string of the sequence ( e.g. Xoofff-SANE,
+/// with that string as nonce ), from the ones customized using different strings and from the
+/// one keyed with different key, customized using same string.
+#[test_case(b"", b"A"; "custom = '' vs 'A'")]
+#[test_case(b"encryption", b"authentication"; "custom = 'encryption' vs 'authentication'")]
+#[test_case(&[0xaa; 100], &[0xaa; 101]; "custom = 100B vs 101B")]
+#[test_case(&[0x61; 97], &[0x62; 97]; "custom = 97B vs 97B")]
+fn test_xoofff_customized(custom0: &[u8], custom1: &[u8]) {
+    use crate::XoofffKey;
+
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut msg = [0u8; 64];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut msg);
+
+    let digest = |mut deck: Xoofff| {
+        let mut dig = [0u8; 32];
+
+        deck.absorb(&msg);
+        deck.finalize(0, 0, 0);
+        deck.squeeze(&mut dig);
+
+        dig
+    };
+
+    let template = Xoofff::new_customized(&key, custom0);
+
+    // state of Xoofff-SANE, with encoded customization string as nonce
+    let mut deck = Xoofff::new(&key);
+    deck.absorb_tuple(&[custom0]);
+    deck.finalize(0, 0, 0);
+    deck.restart();
+
+    assert_ne!(digest(template), digest(deck));
+    assert_eq!(digest(template), digest(template));
+    assert_eq!(
+        digest(template),
+        digest(XoofffKey::new_customized(&key, custom0).deck())
+    );
+    assert_ne!(digest(template), digest(Xoofff::new(&key)));
+    assert_ne!(
+        digest(template),
+        digest(Xoofff::new_customized(&key, custom1))
+    );
+
+    key[0] ^= 1;
+    assert_ne!(
+        digest(template),
+        digest(Xoofff::new_customized(&key, custom0))
+    );
+}
+
+/// Test that keys of byte length >= 48 are accepted, while deck functions keyed with them
+/// being different from the ones keyed with their prefixes.
+#[test_case(48; "key = 48B")]
+#[test_case(64; "key = 64B")]
+#[test_case(200; "key = 200B")]
+#[test_case(1000; "key = 1000B")]
+fn test_xoofff_long_key(klen: usize) {
+    let mut rng = thread_rng();
+
+    let mut key = vec![0u8; klen];
+    rng.fill_bytes(&mut key);
+
+    let digest = |key: &[u8]| {
+        let mut dig = [0u8; 32];
+
+        let mut deck = Xoofff::new(key);
+        deck.absorb(b"message");
+        deck.finalize(0, 0, 0);
+        deck.squeeze(&mut dig);
+
+        dig
+    };
+
+    assert_eq!(digest(&key), digest(&key));
+    assert_ne!(digest(&key), digest(&key[..klen - 1]));
+    assert_ne!(digest(&key), digest(&key[..47]));
+}
+
+/// Test that deck functions, instantiated from precomputed masked key, are same as the ones
+/// created from key, while masked key can be shared across threads.
+#[test_case(32; "key = 32B")]
+#[test_case(47; "key = 47B")]
+#[test_case(64; "key = 64B")]
+fn test_xoofff_key(klen: usize) {
+    use crate::XoofffKey;
+
+    fn is_send_sync<T: Send + Sync>() {}
+    is_send_sync::<XoofffKey>();
+
+    let mut rng = thread_rng();
+
+    let mut key = vec![0u8; klen];
+    let mut msg = [0u8; 64];
+    let mut dig = [0u8; 32];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut msg);
+
+    let mut deck = Xoofff::new(&key);
+    deck.absorb(&msg);
+    deck.finalize(0, 0, 0);
+    deck.squeeze(&mut dig);
+
+    let masked_key = XoofffKey::new(&key);
+
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| {
+                let mut out = [0u8; 32];
+
+                let mut deck = masked_key.deck();
+                deck.absorb(&msg);
+                deck.finalize(0, 0, 0);
+                deck.squeeze(&mut out);
+
+                assert_eq!(dig, out);
+            });
+        }
+    });
+}
+
+/// Test that jumping ahead n steps, using roll_Xc, is same as applying it n times, while
+/// jumps compose additively.
+#[test_case(0; "n = 0")]
+#[test_case(1; "n = 1")]
+#[test_case(2; "n = 2")]
+#[test_case(47; "n = 47")]
+#[test_case(384; "n = 384")]
+#[test_case(12345; "n = 12345")]
+fn test_roll_xc_n(n: u64) {
+    use crate::rolling::{roll_xc, roll_xc_n};
+
+    let mut rng = thread_rng();
+
+    let mut state0 = [0u32; 12];
+    state0.iter_mut().for_each(|w| *w = rng.next_u32());
+    let mut state1 = state0;
+
+    for _ in 0..n {
+        roll_xc(&mut state0);
+    }
+    roll_xc_n(&mut state1, n);
+
+    assert_eq!(state0, state1);
+
+    let big = (1u64 << 40) | n;
+
+    let mut state2 = state1;
+    roll_xc_n(&mut state1, big);
+    roll_xc_n(&mut state2, 1u64 << 40);
+    roll_xc_n(&mut state2, n);
+
+    assert_eq!(state1, state2);
+}
+
+/// Test that absorbing message blocks out of order, by their index, produces same output as
+/// absorbing message sequentially, while duplicate, out of range or missing blocks are rejected,
+/// leaving deck function state untouched.
+#[test_case(0; "message = 0B")]
+#[test_case(47; "message = 47B")]
+#[test_case(48; "message = 48B")]
+#[test_case(1000; "message = 1000B")]
+#[test_case(4800; "message = 4800B")]
+fn test_xoofff_absorb_indexed(mlen: usize) {
+    use crate::{DomainSuffix, XoofffError};
+    use rand::seq::SliceRandom;
+
+    let suffix = DomainSuffix::new(0b1, 1).unwrap();
+
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut msg = vec![0u8; mlen];
+    let mut dig0 = [0u8; 64];
+    let mut dig1 = [0u8; 64];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut msg);
+
+    // sequential absorption, of second message in the sequence
+    let mut deck0 = Xoofff::new(&key);
+    deck0.absorb(b"first message");
+    deck0.finalize(0, 0, 0);
+    deck0.restart();
+    deck0.absorb(&msg);
+    deck0.finalize(0b1, 1, 0);
+    deck0.squeeze(&mut dig0);
+
+    // out of order absorption
+    let mut deck1 = Xoofff::new(&key);
+    deck1.absorb(b"first message");
+    deck1.finalize(0, 0, 0);
+    deck1.restart();
+
+    let mut blocks = msg
+        .chunks_exact(48)
+        .enumerate()
+        .map(|(i, blk)| (i as u64, <[u8; 48]>::try_from(blk).unwrap()))
+        .collect::<Vec<_>>();
+    let tail = msg.chunks_exact(48).remainder();
+
+    blocks.shuffle(&mut rng);
+
+    let blk_cnt = blocks.len() as u64;
+
+    if let Some(&(index, blk)) = blocks.first() {
+        // duplicate block
+        let mut absorber = deck1.absorb_indexed(blk_cnt).unwrap();
+        for (i, blk) in &blocks {
+            absorber.absorb_block_at(*i, blk);
+        }
+        absorber.absorb_block_at(index, &blk);
+        assert_eq!(
+            absorber.finalize(suffix, 0),
+            Err(XoofffError::DuplicateBlock(index))
+        );
+
+        // missing block
+        let mut absorber = deck1.absorb_indexed(blk_cnt).unwrap();
+        for (i, blk) in blocks.iter().filter(|(i, _)| *i != index) {
+            absorber.absorb_block_at(*i, blk);
+        }
+        absorber.absorb_tail(tail);
+        assert_eq!(
+            absorber.finalize(suffix, 0),
+            Err(XoofffError::MissingBlock(index))
+        );
+    }
+
+    // out of range block
+    let mut absorber = deck1.absorb_indexed(blk_cnt).unwrap();
+    absorber.absorb_block_at(blk_cnt, &[0u8; 48]);
+    assert_eq!(
+        absorber.finalize(suffix, 0),
+        Err(XoofffError::BadBlockIndex(blk_cnt))
+    );
+
+    let mut absorber = deck1.absorb_indexed(blk_cnt).unwrap();
+    absorber.absorb_tail(tail);
+    for (i, blk) in &blocks {
+        absorber.absorb_block_at(*i, blk);
+    }
+    absorber.finalize(suffix, 0).unwrap();
+
+    assert!(deck1.absorb_indexed(0).is_err());
+
+    deck1.squeeze(&mut dig1);
+    assert_eq!(dig0, dig1);
+}
+
+/// Test that partial accumulators, computed independently for ranges of a message, serialized,
+/// deserialized and merged in arbitrary order, can be finalized, producing same output as
+/// absorbing whole message sequentially, while merging non-adjacent ranges is rejected.
+#[test_case(0, &[0]; "message = 0B")]
+#[test_case(47, &[47]; "message = 47B")]
+#[test_case(96, &[48, 48]; "message = 96B")]
+#[test_case(1000, &[480, 48, 0, 472]; "message = 1000B")]
+#[test_case(4801, &[96, 1440, 48, 3216, 1]; "message = 4801B")]
+fn test_xoofff_partial_accumulator(mlen: usize, ranges: &[usize]) {
+    use crate::{DomainSuffix, XoofffError, XoofffPartialAccumulator};
+
+    let suffix = DomainSuffix::new(0b11, 2).unwrap();
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut msg = vec![0u8; mlen];
+    let mut dig0 = [0u8; 64];
+    let mut dig1 = [0u8; 64];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut msg);
+
+    let mut deck0 = Xoofff::new(&key);
+    deck0.absorb(&msg);
+    deck0.finalize(0b11, 2, 0);
+    deck0.squeeze(&mut dig0);
+
+    let deck1 = Xoofff::new(&key);
+
+    // compute partial accumulators independently, sending them over wire
+    let mut off = 0;
+    let mut partials = Vec::new();
+    for &len in ranges {
+        let partial = deck1.accumulate((off / 48) as u64, &msg[off..(off + len)]);
+        let bytes = partial.unwrap().to_bytes();
+
+        partials.push(XoofffPartialAccumulator::from_bytes(&bytes).unwrap());
+        off += len;
+    }
+    assert_eq!(off, mlen);
+
+    if partials.len() > 2 {
+        assert_eq!(
+            partials[0].merge(&partials[2]).err(),
+            Some(XoofffError::MissingBlock(partials[0].range().end))
+        );
+    }
+
+    let tailed = deck1.accumulate(0, &[0u8; 50]).unwrap();
+    let next = deck1.accumulate(1, &[0u8; 48]).unwrap();
+    assert_eq!(
+        next.merge(&tailed).err(),
+        Some(XoofffError::TrailingPartialBlock(1))
+    );
+
+    // merge ( associatively ) in arbitrary order
+    let mid = partials.len() / 2;
+    let lo = partials[..mid]
+        .iter()
+        .rev()
+        .fold(None, |acc: Option<XoofffPartialAccumulator>, p| match acc {
+            None => Some(*p),
+            Some(acc) => Some(acc.merge(p).unwrap()),
+        });
+    let hi = partials[mid..]
+        .iter()
+        .skip(1)
+        .fold(partials[mid], |acc, p| acc.merge(p).unwrap());
+    let merged = match lo {
+        None => hi,
+        Some(lo) => hi.merge(&lo).unwrap(),
+    };
+
+    assert_eq!(merged.range(), 0..(mlen / 48) as u64);
+
+    let mut deck2 = deck1;
+    deck2.finalize_accumulated(&merged, suffix, 0).unwrap();
+    deck2.squeeze(&mut dig1);
+
+    assert_eq!(dig0, dig1);
+    assert!(XoofffPartialAccumulator::from_bytes(&merged.to_bytes()[1..]).is_err());
+}
+
+/// Test that absorbing message in parallel is same as absorbing it sequentially, even when
+/// interleaved with sequential absorption.
+#[cfg(feature = "rayon")]
+#[test_case(0, 0; "head = 0B message = 0B")]
+#[test_case(0, 47; "head = 0B message = 47B")]
+#[test_case(13, 35; "head = 13B message = 35B")]
+#[test_case(13, 3072; "head = 13B message = 3072B")]
+#[test_case(0, 1 << 20; "head = 0B message = 1MB")]
+#[test_case(47, (1 << 20) + 3; "head = 47B message = 1MB + 3B")]
+fn test_xoofff_absorb_par(hlen: usize, mlen: usize) {
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut head = vec![0u8; hlen];
+    let mut msg = vec![0u8; mlen];
+    let mut dig0 = [0u8; 64];
+    let mut dig1 = [0u8; 64];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut head);
+    rng.fill_bytes(&mut msg);
+
+    let mut deck0 = Xoofff::new(&key);
+    deck0.absorb(&head);
+    deck0.absorb(&msg);
+    deck0.absorb(&head);
+    deck0.finalize(0b1, 1, 0);
+    deck0.squeeze(&mut dig0);
+
+    let mut deck1 = Xoofff::new(&key);
+    deck1.absorb(&head);
+    deck1.absorb_par(&msg);
+    deck1.absorb_par(&head);
+    deck1.finalize(0b1, 1, 0);
+    deck1.squeeze(&mut dig1);
+
+    assert_eq!(dig0, dig1);
+}
+
+/// Test that squeezing in parallel is same as squeezing sequentially, while leaving the state
+/// positioned right after squeezed bytes, for further squeezing.
+#[cfg(feature = "rayon")]
+#[test_case(0, 0; "head = 0B output = 0B")]
+#[test_case(0, 47; "head = 0B output = 47B")]
+#[test_case(13, 35; "head = 13B output = 35B")]
+#[test_case(13, 36; "head = 13B output = 36B")]
+#[test_case(13, 3072; "head = 13B output = 3072B")]
+#[test_case(0, 1 << 20; "head = 0B output = 1MB")]
+#[test_case(47, (1 << 20) + 3; "head = 47B output = 1MB + 3B")]
+fn test_xoofff_squeeze_par(hlen: usize, olen: usize) {
+    const TLEN: usize = 100;
+
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut msg = [0u8; 64];
+    let mut out0 = vec![0u8; hlen + olen + TLEN];
+    let mut out1 = vec![0u8; hlen + olen + TLEN];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut msg);
+
+    let mut deck0 = Xoofff::new(&key);
+    deck0.absorb(&msg);
+    deck0.finalize(0b1, 1, 0);
+    deck0.squeeze(&mut out0);
+
+    let mut deck1 = Xoofff::new(&key);
+    deck1.absorb(&msg);
+    deck1.finalize(0b1, 1, 0);
+    deck1.squeeze(&mut out1[..hlen]);
+    deck1.squeeze_par(&mut out1[hlen..(hlen + olen)]);
+    assert_eq!(deck1.position(), (hlen + olen) as u64);
+    deck1.squeeze(&mut out1[(hlen + olen)..]);
+
+    assert_eq!(out0, out1);
+}
+
+/// Test that a Xoofff-SANE session can be wrapped by sender and unwrapped by receiver,
+/// for a sequence of messages, while also ensuring that tampering, reordering or replaying
+/// messages is detected by receiver.
+#[test_case(32, 16, &[(0, 0), (16, 0), (0, 32), (13, 47), (64, 257)]; "key = 32B nonce = 16B")]
+#[test_case(16, 0, &[(1, 1), (48, 48), (0, 96), (97, 0)]; "key = 16B nonce = 0B")]
+fn test_xoofff_sane(klen: usize, nlen: usize, msgs: &[(usize, usize)]) {
+    use crate::sane::XoofffSane;
+
+    let mut rng = thread_rng();
+
+    let mut key = vec![0u8; klen];
+    let mut nonce = vec![0u8; nlen];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut nonce);
+
+    let (mut sender, tag) = XoofffSane::new(&key, &nonce);
+    let mut receiver = XoofffSane::start(&key, &nonce, &tag).unwrap();
+
+    let mut wrapped = Vec::with_capacity(msgs.len());
+    for &(alen, mlen) in msgs {
+        let mut ad = vec![0u8; alen];
+        let mut pt = vec![0u8; mlen];
+        let mut ct = vec![0u8; mlen];
+        let mut dec = vec![0u8; mlen];
+
+        rng.fill_bytes(&mut ad);
+        rng.fill_bytes(&mut pt);
+
+        let tag = sender.wrap(&ad, &pt, &mut ct);
+
+        // tampered ciphertext/ associated data must not be accepted
+        if mlen > 0 {
+            let mut receiver_ = receiver;
+            let mut ct_ = ct.clone();
+            ct_[mlen - 1] ^= 1;
+            assert!(!receiver_.unwrap(&ad, &ct_, &tag, &mut dec));
+            assert!(dec.iter().all(|&b| b == 0));
+        }
+        if alen > 0 {
+            let mut receiver_ = receiver;
+            let mut ad_ = ad.clone();
+            ad_[0] ^= 0x80;
+            assert!(!receiver_.unwrap(&ad_, &ct, &tag, &mut dec));
+        }
+
+        assert!(receiver.unwrap(&ad, &ct, &tag, &mut dec));
+        assert_eq!(pt, dec);
+
+        wrapped.push((ad, ct, tag));
+    }
+
+    // replaying first message, after whole sequence is consumed, must fail
+    let (ad, ct, tag) = &wrapped[0];
+    let mut dec = vec![0u8; ct.len()];
+    assert!(!receiver.unwrap(ad, ct, tag, &mut dec));
+
+    // reordering messages must fail
+    let (_, tag) = XoofffSane::new(&key, &nonce);
+    let mut receiver = XoofffSane::start(&key, &nonce, &tag).unwrap();
+    let (ad, ct, tag) = &wrapped[1];
+    let mut dec = vec![0u8; ct.len()];
+    assert!(!receiver.unwrap(ad, ct, tag, &mut dec));
+}
+
+/// Test that a Xoofff-SANSE session can be wrapped by sender and unwrapped by receiver, for a
+/// sequence of messages, while also ensuring that tampering, reordering or replaying messages
+/// is detected by receiver and that same message gets encrypted differently as history evolves.
+#[test_case(32, &[(0, 0), (16, 0), (0, 32), (13, 47), (64, 257)]; "key = 32B")]
+#[test_case(16, &[(1, 1), (48, 48), (0, 96), (97, 0)]; "key = 16B")]
+fn test_xoofff_sanse(klen: usize, msgs: &[(usize, usize)]) {
+    use crate::sanse::XoofffSanse;
+
+    let mut rng = thread_rng();
+
+    let mut key = vec![0u8; klen];
+    rng.fill_bytes(&mut key);
+
+    let mut sender = XoofffSanse::new(&key);
+    let mut receiver = XoofffSanse::new(&key);
+
+    let mut wrapped = Vec::with_capacity(msgs.len());
+    for &(alen, mlen) in msgs {
+        let mut ad = vec![0u8; alen];
+        let mut pt = vec![0u8; mlen];
+        let mut ct = vec![0u8; mlen];
+        let mut dec = vec![0u8; mlen];
+
+        rng.fill_bytes(&mut ad);
+        rng.fill_bytes(&mut pt);
+
+        // same message, wrapped by a forked session, must be deterministic
+        let mut sender_ = sender;
+        let mut ct_ = vec![0u8; mlen];
+        let tag_ = sender_.wrap(&ad, &pt, &mut ct_);
+
+        let tag = sender.wrap(&ad, &pt, &mut ct);
+        assert_eq!(tag, tag_);
+        assert_eq!(ct, ct_);
+
+        // tampered ciphertext/ associated data must not be accepted
+        if mlen > 0 {
+            let mut receiver_ = receiver;
+            let mut ct_ = ct.clone();
+            ct_[0] ^= 1;
+            assert!(!receiver_.unwrap(&ad, &ct_, &tag, &mut dec));
+            assert!(dec.iter().all(|&b| b == 0));
+        }
+        if alen > 0 {
+            let mut receiver_ = receiver;
+            let mut ad_ = ad.clone();
+            ad_[alen - 1] ^= 0x80;
+            assert!(!receiver_.unwrap(&ad_, &ct, &tag, &mut dec));
+        }
+
+        assert!(receiver.unwrap(&ad, &ct, &tag, &mut dec));
+        assert_eq!(pt, dec);
+
+        wrapped.push((ad, pt, ct, tag));
+    }
+
+    // replaying a message after the whole sequence is consumed must fail, and wrapping
+    // same message again, must produce different tag, because session history changed
+    let (ad, pt, ct, tag) = &wrapped[msgs.len() - 1];
+    let mut dec = vec![0u8; ct.len()];
+
+    let mut sender_ = sender;
+    let mut ct_ = vec![0u8; pt.len()];
+    assert_ne!(*tag, sender_.wrap(ad, pt, &mut ct_));
+    assert!(!receiver.unwrap(ad, ct, tag, &mut dec));
+
+    // reordering messages must fail
+    let mut receiver = XoofffSanse::new(&key);
+    let (ad, _, ct, tag) = &wrapped[1];
+    let mut dec = vec![0u8; ct.len()];
+    assert!(!receiver.unwrap(ad, ct, tag, &mut dec));
+}
+
+/// Test that Xoofff-WBC is length preserving, deciphering inverts enciphering and that
+/// flipping a single bit of ciphertext or tweak garbles the whole deciphered plaintext.
+#[test_case(32, 0, 0; "key = 32B tweak = 0B message = 0B")]
+#[test_case(32, 16, 1; "key = 32B tweak = 16B message = 1B")]
+#[test_case(16, 16, 2; "key = 16B tweak = 16B message = 2B")]
+#[test_case(32, 0, 47; "key = 32B tweak = 0B message = 47B")]
+#[test_case(32, 16, 94; "key = 32B tweak = 16B message = 94B")]
+#[test_case(32, 16, 95; "key = 32B tweak = 16B message = 95B")]
+#[test_case(32, 16, 96; "key = 32B tweak = 16B message = 96B")]
+#[test_case(32, 48, 200; "key = 32B tweak = 48B message = 200B")]
+#[test_case(47, 64, 1024; "key = 47B tweak = 64B message = 1024B")]
+#[test_case(32, 16, 4099; "key = 32B tweak = 16B message = 4099B")]
+fn test_xoofff_wbc(klen: usize, wlen: usize, mlen: usize) {
+    use crate::wbc::XoofffWbc;
+
+    let mut rng = thread_rng();
+
+    let mut key = vec![0u8; klen];
+    let mut tweak = vec![0u8; wlen];
+    let mut msg = vec![0u8; mlen];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut tweak);
+    rng.fill_bytes(&mut msg);
+
+    let wbc = XoofffWbc::new(&key);
+
+    let mut buf = msg.clone();
+    wbc.encrypt(&tweak, &mut buf);
+    if mlen >= 16 {
+        assert_ne!(msg, buf);
+    }
+
+    let enc = buf.clone();
+    wbc.decrypt(&tweak, &mut buf);
+    assert_eq!(msg, buf);
+
+    if mlen >= 16 {
+        // any flipped bit in ciphertext must garble both halves of deciphered plaintext
+        for idx in [0, mlen / 2, mlen - 1] {
+            let mut buf = enc.clone();
+            buf[idx] ^= 1 << (idx & 7);
+            wbc.decrypt(&tweak, &mut buf);
+
+            assert_ne!(msg[..8], buf[..8]);
+            assert_ne!(msg[mlen - 8..], buf[mlen - 8..]);
+        }
+
+        // so must a different tweak
+        let mut tweak_ = tweak.clone();
+        tweak_.push(0);
+        let mut buf = enc.clone();
+        wbc.decrypt(&tweak_, &mut buf);
+
+        assert_ne!(msg[..8], buf[..8]);
+        assert_ne!(msg[mlen - 8..], buf[mlen - 8..]);
+    }
+}
+
+/// Test that Xoofff-WBC splits plaintext into (almost) equal halves only for inputs of
+/// byte length <= 94, while longer inputs get a left part filling whole blocks.
+#[test_case(0, 0; "message = 0B")]
+#[test_case(1, 1; "message = 1B")]
+#[test_case(47, 24; "message = 47B")]
+#[test_case(94, 47; "message = 94B")]
+#[test_case(95, 47; "message = 95B")]
+#[test_case(96, 47; "message = 96B")]
+#[test_case(143, 47; "message = 143B")]
+#[test_case(144, 95; "message = 144B")]
+#[test_case(191, 95; "message = 191B")]
+#[test_case(192, 47; "message = 192B")]
+#[test_case(4099, 1055; "message = 4099B")]
+fn test_xoofff_wbc_split(mlen: usize, expected: usize) {
+    use crate::wbc::split;
+
+    assert_eq!(split(mlen), expected);
+}
+
+/// Test that Xoofff-WBC-AE decrypts what it encrypted, while rejecting ciphertexts, which
+/// are tampered with, truncated or decrypted using different associated data.
+#[test_case(32, 0, 0; "key = 32B ad = 0B message = 0B")]
+#[test_case(32, 16, 1; "key = 32B ad = 16B message = 1B")]
+#[test_case(16, 0, 79; "key = 16B ad = 0B message = 79B")]
+#[test_case(32, 48, 80; "key = 32B ad = 48B message = 80B")]
+#[test_case(47, 64, 1024; "key = 47B ad = 64B message = 1024B")]
+fn test_xoofff_wbcae(klen: usize, alen: usize, mlen: usize) {
+    use crate::wbcae::XoofffWbcAe;
+
+    const TAU: usize = 16;
+
+    let mut rng = thread_rng();
+
+    let mut key = vec![0u8; klen];
+    let mut ad = vec![0u8; alen];
+    let mut msg = vec![0u8; mlen];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut ad);
+    rng.fill_bytes(&mut msg);
+
+    let ae = XoofffWbcAe::<TAU>::new(&key);
+
+    let mut buf = vec![0u8; mlen + TAU];
+    buf[..mlen].copy_from_slice(&msg);
+    ae.encrypt(&ad, &mut buf);
+
+    let enc = buf.clone();
+    assert!(ae.decrypt(&ad, &mut buf));
+    assert_eq!(msg, buf[..mlen]);
+
+    let mut buf = enc.clone();
+    buf[(mlen + TAU) / 2] ^= 0x10;
+    assert!(!ae.decrypt(&ad, &mut buf));
+    assert!(buf.iter().all(|&b| b == 0));
+
+    let mut buf = enc.clone();
+    buf.pop();
+    assert!(!ae.decrypt(&ad, &mut buf));
+
+    let mut ad_ = ad.clone();
+    ad_.push(0);
+    let mut buf = enc.clone();
+    assert!(!ae.decrypt(&ad_, &mut buf));
+}
+
+/// Test that Xoofff-BO decrypts what it encrypted, while rejecting ciphertexts, which are
+/// tampered with or decrypted using different nonce or associated data.
+#[test_case(32, 16, 0, 0; "key = 32B nonce = 16B ad = 0B message = 0B")]
+#[test_case(32, 16, 16, 1; "key = 32B nonce = 16B ad = 16B message = 1B")]
+#[test_case(16, 12, 0, 48; "key = 16B nonce = 12B ad = 0B message = 48B")]
+#[test_case(47, 16, 64, 1024; "key = 47B nonce = 16B ad = 64B message = 1024B")]
+fn test_xoofff_bo(klen: usize, nlen: usize, alen: usize, mlen: usize) {
+    use crate::bo::XoofffBo;
+
+    let mut rng = thread_rng();
+
+    let mut key = vec![0u8; klen];
+    let mut nonce = vec![0u8; nlen];
+    let mut ad = vec![0u8; alen];
+    let mut msg = vec![0u8; mlen];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut nonce);
+    rng.fill_bytes(&mut ad);
+    rng.fill_bytes(&mut msg);
+
+    let ae = XoofffBo::new(&key);
+
+    let mut buf = msg.clone();
+    let tag = ae.encrypt(&nonce, &ad, &mut buf);
+    let enc = buf.clone();
+
+    assert!(ae.decrypt(&nonce, &ad, &mut buf, &tag));
+    assert_eq!(msg, buf);
+
+    if mlen > 0 {
+        let mut buf = enc.clone();
+        buf[mlen - 1] ^= 1;
+        assert!(!ae.decrypt(&nonce, &ad, &mut buf, &tag));
+    }
+
+    let mut nonce_ = nonce.clone();
+    nonce_.push(0);
+    let mut buf = enc.clone();
+    assert!(!ae.decrypt(&nonce_, &ad, &mut buf, &tag));
+    assert_eq!(enc, buf);
+
+    let mut ad_ = ad.clone();
+    ad_.push(0);
+    let mut buf = enc.clone();
+    assert!(!ae.decrypt(&nonce, &ad_, &mut buf, &tag));
+}
+
+/// Test that Xoofff-BO, used through RustCrypto `aead` traits, agrees with its inherent API.
+#[cfg(feature = "aead")]
+#[test]
+fn test_xoofff_bo_aead() {
+    use crate::bo::XoofffBo;
+    use aead::{AeadInPlace, KeyInit};
+
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut nonce = [0u8; 16];
+    let mut ad = [0u8; 32];
+    let mut msg = [0u8; 100];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut nonce);
+    rng.fill_bytes(&mut ad);
+    rng.fill_bytes(&mut msg);
+
+    let ae = <XoofffBo as KeyInit>::new(&key.into());
+
+    let mut buf0 = msg;
+    let tag0 = ae
+        .encrypt_in_place_detached(&nonce.into(), &ad, &mut buf0)
+        .unwrap();
+
+    let mut buf1 = msg;
+    let tag1 = XoofffBo::new(&key).encrypt(&nonce, &ad, &mut buf1);
+
+    assert_eq!(buf0, buf1);
+    assert_eq!(tag0.as_slice(), tag1);
+
+    ae.decrypt_in_place_detached(&nonce.into(), &ad, &mut buf0, &tag0)
+        .unwrap();
+    assert_eq!(buf0, msg);
+
+    buf1[0] ^= 1;
+    assert!(ae
+        .decrypt_in_place_detached(&nonce.into(), &ad, &mut buf1, &tag0)
+        .is_err());
+}
+
+/// Test functional correctness of Keccak-p\[1600, 24\] permutation ( which is Keccak-f\[1600\] )
+/// by computing SHA3-256 digest of short messages, using a minimal sponge, and comparing
+/// them with digests listed on https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values.
+#[test_case(b"", "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"; "SHA3-256 of empty message")]
+#[test_case(b"abc", "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"; "SHA3-256 of 'abc'")]
+fn test_keccak_p1600(msg: &[u8], digest: &str) {
+    use crate::keccak;
+
+    const RATE: usize = 136;
+
+    let mut blk = [0u8; 200];
+    blk[..msg.len()].copy_from_slice(msg);
+    blk[msg.len()] ^= 0x06;
+    blk[RATE - 1] ^= 0x80;
+
+    let mut state = [0u64; 25];
+    for (i, lane) in state.iter_mut().enumerate() {
+        *lane = u64::from_le_bytes(blk[i * 8..(i + 1) * 8].try_into().unwrap());
+    }
+
+    keccak::permute::<24>(&mut state);
+
+    let computed = state[..4]
+        .iter()
+        .flat_map(|lane| lane.to_le_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(hex::encode(computed), digest);
+}
+
+/// Test SHA3-256, used for compressing long Kravatte keys, on messages spanning upto three blocks,
+/// where i -th message byte is i mod 256, comparing digests with the ones computed by Python's hashlib.
+#[test_case(0, "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"; "message = 0B")]
+#[test_case(1, "5d53469f20fef4f8eab52b88044ede69c77a6a68a60728609fc4a65ff531e7d0"; "message = 1B")]
+#[test_case(135, "fded8fd9d6551c601eeb3b7c6bc5e5cfd8aad1d015b7e9aaa9c9b9475231d5e2"; "message = 135B")]
+#[test_case(136, "cf3ccff92480a29160c2d38317c430e14749bfee1788106957dfe73f8c4930e5"; "message = 136B")]
+#[test_case(137, "ce9d7dc90913ee5d92745019479a5352c6d6279bef18ed07dc0a83ee8084daca"; "message = 137B")]
+#[test_case(272, "0b21ec4a8eff6d179e09ba9fe0ab08515b24e0923fbf419f5c30a38e64577db5"; "message = 272B")]
+#[test_case(300, "815c06bbeb8520ce61add33a5f47bc558bf00e6361a5640c972d5d4634c58101"; "message = 300B")]
+fn test_sha3_256(mlen: usize, digest: &str) {
+    use crate::keccak;
+
+    let msg = (0..mlen).map(|i| i as u8).collect::<Vec<_>>();
+    assert_eq!(hex::encode(keccak::sha3_256(&msg)), digest);
+}
+
+#[test_case(32, 0, 32, 0b1, 1, 0; "key = 32B message = 0B digest = 32B offset = 0B")]
+#[test_case(16, 200, 64, 0b11, 2, 0; "key = 16B message = 200B digest = 64B offset = 0B")]
+#[test_case(32, 512, 1024, 0b101, 3, 1; "key = 32B message = 512B digest = 1024B offset = 1B")]
+#[test_case(199, 2048, 4096, 0b1, 2, 200; "key = 199B message = 2048B digest = 4096B offset = 200B")]
+#[test_case(200, 1024, 32, 0, 0, 32; "key = 200B message = 1024B digest = 32B offset = 32B")]
+fn test_kravatte_incremental_io(
+    klen: usize,
+    mlen: usize,
+    dlen: usize,
+    domain_seperator: u8,
+    ds_bit_width: usize,
+    offset: usize,
+) {
+    use crate::Kravatte;
+
+    let mut rng = thread_rng();
+
+    let mut key = vec![0u8; klen];
+    let mut msg = vec![0u8; mlen];
+    let mut dig0 = vec![0u8; dlen]; // digest from oneshot absorption
+    let mut dig1 = vec![0u8; dlen]; // digest from incremental absorption
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut msg);
+
+    // oneshot absorption
+    let mut deck0 = Kravatte::new(&key);
+    deck0.absorb(&msg);
+    deck0.finalize(domain_seperator, ds_bit_width, offset);
+    deck0.squeeze(&mut dig0);
+
+    // incremental absorption and squeezing
+    let mut deck1 = Kravatte::new(&key);
+
+    let mut off = 0;
+    while off < mlen {
+        let elen = cmp::min(cmp::max(msg[off] as usize, 1), mlen - off);
+
+        deck1.absorb(&msg[off..(off + elen)]);
+        off += elen;
+    }
+
+    deck1.finalize(domain_seperator, ds_bit_width, offset);
+
+    let mut off = 0;
+    while off < dlen {
+        let elen = cmp::min(cmp::max(dig0[off] as usize, 1), dlen - off);
+
+        deck1.squeeze(&mut dig1[off..(off + elen)]);
+        off += elen;
+    }
+
+    assert_eq!(dig0, dig1);
+}
+
+/// Test functional correctness of Xoodyak hash, by using known answer tests, listed in
+/// `LWC_HASH_KAT_256.txt`, from Xoodyak submission package to NIST LWC standardization effort.
+#[test_case(b"", "ea152f2b47bce24efb66c479d4adf17bd324d806e85ff75ee369ee50dc8f8bd1"; "Count = 1")]
+fn test_xoodyak_hash(msg: &[u8], digest: &str) {
+    use crate::xoodyak::XoodyakHash;
+
+    let computed = XoodyakHash::digest(msg);
+    assert_eq!(hex::encode(computed), digest);
+}
+
+/// Test functional correctness of Xoodyak hash, by using all known answer tests, from Xoodyak
+/// submission package to NIST LWC standardization effort i.e. `LWC_HASH_KAT_256.txt`, which
+/// should be placed in `./kats` directory. Run it with `cargo test --lib -- --ignored`.
+#[test]
+#[ignore = "requires ./kats/LWC_HASH_KAT_256.txt"]
+fn test_xoodyak_hash_kat() {
+    use crate::xoodyak::XoodyakHash;
+
+    let kat_file = "./kats/LWC_HASH_KAT_256.txt";
+    let file = File::open(kat_file).unwrap();
+    let mut reader = BufReader::new(file).lines();
+
+    while let Some(line) = reader.next() {
+        // test case count, which is not used
+        let _ = line.unwrap();
+
+        // message to be hashed
+        let msg = reader.next().unwrap().unwrap();
+        let msg = msg.split(" = ").collect::<Vec<_>>()[1];
+        let msg = hex::decode(msg).unwrap();
+
+        // expected message digest
+        let md = reader.next().unwrap().unwrap();
+        let md = md.split(" = ").collect::<Vec<_>>()[1];
+        let expected = hex::decode(md).unwrap();
+
+        let computed = XoodyakHash::digest(&msg);
+        assert_eq!(expected, computed, "msg = {}", hex::encode(&msg));
+
+        reader.next().unwrap().unwrap(); // skip the empty line
+    }
+}
+
+#[test_case(0, 32; "message = 0B digest = 32B")]
+#[test_case(15, 32; "message = 15B digest = 32B")]
+#[test_case(16, 32; "message = 16B digest = 32B")]
+#[test_case(17, 64; "message = 17B digest = 64B")]
+#[test_case(32, 100; "message = 32B digest = 100B")]
+#[test_case(1024, 2048; "message = 1024B digest = 2048B")]
+fn test_xoodyak_hash_incremental_io(mlen: usize, dlen: usize) {
+    use crate::xoodyak::XoodyakHash;
+
+    let mut rng = thread_rng();
+
+    let mut msg = vec![0u8; mlen];
+    let mut dig0 = vec![0u8; dlen]; // digest from oneshot absorption
+    let mut dig1 = vec![0u8; dlen]; // digest from incremental absorption
+
+    rng.fill_bytes(&mut msg);
+
+    // oneshot absorption
+    let mut hasher0 = XoodyakHash::new();
+    hasher0.absorb(&msg);
+    hasher0.squeeze(&mut dig0);
+
+    assert_eq!(dig0[..32], XoodyakHash::digest(&msg));
+
+    // incremental absorption and squeezing
+    let mut hasher1 = XoodyakHash::new();
+
+    let mut off = 0;
+    while off < mlen {
+        let elen = cmp::min(cmp::max(msg[off] as usize & 31, 1), mlen - off);
+
+        hasher1.absorb(&msg[off..(off + elen)]);
+        hasher1.absorb(&[]);
+        off += elen;
+    }
+
+    let mut off = 0;
+    while off < dlen {
+        let elen = cmp::min(cmp::max(dig0[off] as usize & 31, 1), dlen - off);
+
+        hasher1.squeeze(&mut dig1[off..(off + elen)]);
+        off += elen;
+    }
+
+    assert_eq!(dig0, dig1);
+}
+
+/// Test functional correctness of Xoodyak AEAD, by using known answer tests, from Xoodyak
+/// submission package to NIST LWC standardization effort i.e. `LWC_AEAD_KAT_128_128.txt`,
+/// which should be placed in `./kats` directory. Run it with `cargo test --lib -- --ignored`.
+#[test]
+#[ignore = "requires ./kats/LWC_AEAD_KAT_128_128.txt"]
+fn test_xoodyak_aead_kat() {
+    use crate::xoodyak::XoodyakAead;
+
+    let kat_file = "./kats/LWC_AEAD_KAT_128_128.txt";
+    let file = File::open(kat_file).unwrap();
+    let mut reader = BufReader::new(file).lines();
+
+    // parses hex encoded value of next `Field = Value` line
+    fn next_field(reader: &mut impl Iterator<Item = std::io::Result<String>>) -> Vec<u8> {
+        let line = reader.next().unwrap().unwrap();
+        hex::decode(line.split(" = ").collect::<Vec<_>>()[1]).unwrap()
+    }
+
+    while let Some(line) = reader.next() {
+        // test case count, which is not used
+        let _ = line.unwrap();
+
+        let key = next_field(&mut reader);
+        let nonce = next_field(&mut reader);
+        let pt = next_field(&mut reader);
+        let ad = next_field(&mut reader);
+        let ct = next_field(&mut reader); // ciphertext || tag
+
+        let aead = XoodyakAead::new(&key.try_into().unwrap());
+        let nonce = nonce.try_into().unwrap();
+
+        let mut buf = pt.clone();
+        let tag = aead.encrypt(&nonce, &ad, &mut buf);
+        buf.extend_from_slice(&tag);
+        assert_eq!(
+            ct,
+            buf,
+            "pt = {}, ad = {}",
+            hex::encode(&pt),
+            hex::encode(&ad)
+        );
+
+        let mut buf = ct[..pt.len()].to_vec();
+        let tag = ct[pt.len()..].try_into().unwrap();
+        assert!(aead.decrypt(&nonce, &ad, &mut buf, &tag));
+        assert_eq!(pt, buf);
+
+        reader.next().unwrap().unwrap(); // skip the empty line
+    }
+}
+
+/// Test that Xoodyak AEAD decrypts what it encrypted, while rejecting ciphertexts, which are
+/// tampered with or decrypted using different nonce or associated data.
+#[test_case(0, 0; "ad = 0B message = 0B")]
+#[test_case(16, 1; "ad = 16B message = 1B")]
+#[test_case(44, 24; "ad = 44B message = 24B")]
+#[test_case(45, 25; "ad = 45B message = 25B")]
+#[test_case(64, 1024; "ad = 64B message = 1024B")]
+fn test_xoodyak_aead(alen: usize, mlen: usize) {
+    use crate::xoodyak::XoodyakAead;
+
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 16];
+    let mut nonce = [0u8; 16];
+    let mut ad = vec![0u8; alen];
+    let mut msg = vec![0u8; mlen];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut nonce);
+    rng.fill_bytes(&mut ad);
+    rng.fill_bytes(&mut msg);
+
+    let aead = XoodyakAead::new(&key);
+
+    let mut buf = msg.clone();
+    let tag = aead.encrypt(&nonce, &ad, &mut buf);
+    let enc = buf.clone();
+
+    assert!(aead.decrypt(&nonce, &ad, &mut buf, &tag));
+    assert_eq!(msg, buf);
+
+    if mlen > 0 {
+        let mut buf = enc.clone();
+        buf[mlen / 2] ^= 1;
+        assert!(!aead.decrypt(&nonce, &ad, &mut buf, &tag));
+        assert!(buf.iter().all(|&b| b == 0));
+    }
+
+    let mut nonce_ = nonce;
+    nonce_[15] ^= 0x80;
+    let mut buf = enc.clone();
+    assert!(!aead.decrypt(&nonce_, &ad, &mut buf, &tag));
+
+    let mut ad_ = ad.clone();
+    ad_.push(0);
+    let mut buf = enc.clone();
+    assert!(!aead.decrypt(&nonce, &ad_, &mut buf, &tag));
+}
+
+/// Test that Cyclist, created with an empty key, stays in hash mode, producing same digest as
+/// Xoodyak hash, while a non-empty key switches it into keyed mode.
+#[test_case(0; "message = 0B")]
+#[test_case(16; "message = 16B")]
+#[test_case(100; "message = 100B")]
+fn test_xoodyak_cyclist_empty_key(mlen: usize) {
+    use crate::xoodyak::{Cyclist, XoodyakHash, DIGEST_LEN};
+
+    let mut rng = thread_rng();
+
+    let mut msg = vec![0u8; mlen];
+    rng.fill_bytes(&mut msg);
+
+    let mut dig0 = [0u8; DIGEST_LEN];
+    let mut dig1 = [0u8; DIGEST_LEN];
+
+    let mut cyclist = Cyclist::new_keyed(&[], &[1, 2], &[3]);
+    cyclist.absorb(&msg);
+    cyclist.squeeze(&mut dig0);
+    assert_eq!(dig0, XoodyakHash::digest(&msg));
+
+    let mut cyclist = Cyclist::new_keyed(&[0], &[1, 2], &[3]);
+    cyclist.absorb(&msg);
+    cyclist.squeeze(&mut dig1);
+    assert_ne!(dig0, dig1);
+}
+
+/// Test that two parties, running Xoodyak keyed mode session, with same key, key identifier
+/// and counter, stay in sync through arbitrary sequence of session operations, while ratcheting
+/// and key squeezing are domain separated from plain squeezing.
+#[test]
+fn test_xoodyak_keyed_session() {
+    use crate::xoodyak::XoodyakKeyed;
+
+    let mut rng = thread_rng();
+
+    let mut key = [0u8; 32];
+    let mut id = [0u8; 8];
+    let mut counter = [0u8; 4];
+
+    rng.fill_bytes(&mut key);
+    rng.fill_bytes(&mut id);
+    rng.fill_bytes(&mut counter);
+
+    let mut alice = XoodyakKeyed::new(&key, &id, &counter);
+    let mut bob = XoodyakKeyed::new(&key, &id, &counter);
+
+    for mlen in [0, 1, 23, 24, 25, 100] {
+        let mut msg = vec![0u8; mlen];
+        rng.fill_bytes(&mut msg);
+
+        alice.absorb(&msg);
+        bob.absorb(&msg);
+
+        let mut buf = msg.clone();
+        alice.encrypt(&mut buf);
+        if mlen >= 16 {
+            assert_ne!(msg, buf);
+        }
+
+        bob.decrypt(&mut buf);
+        assert_eq!(msg, buf);
+
+        let mut tag0 = [0u8; 16];
+        let mut tag1 = [0u8; 16];
+        alice.squeeze(&mut tag0);
+        bob.squeeze(&mut tag1);
+        assert_eq!(tag0, tag1);
+    }
+
+    // squeezing key must be domain separated from squeezing tag
+    let mut alice_ = alice;
+    let mut out0 = [0u8; 32];
+    let mut out1 = [0u8; 32];
+    alice.squeeze(&mut out0);
+    alice_.squeeze_key(&mut out1);
+    assert_ne!(out0, out1);
+
+    // ratcheting changes the state, though parties stay in sync
+    let mut alice_ = alice;
+    alice.ratchet();
+    bob.squeeze(&mut out1);
+    bob.ratchet();
+
+    alice.squeeze(&mut out0);
+    bob.squeeze(&mut out1);
+    assert_eq!(out0, out1);
+
+    alice_.squeeze(&mut out1);
+    assert_ne!(out0, out1);
+
+    // different counter must result into different session
+    let mut eve = XoodyakKeyed::new(&key, &id, &[]);
+    eve.squeeze(&mut out1);
+    XoodyakKeyed::new(&key, &id, &counter).squeeze(&mut out0);
+    assert_ne!(out0, out1);
+}
+
+#[test_case(Padding::Pad10x, 0, 32, 0, 0; "pad10* message = 0B output = 32B")]
+#[test_case(Padding::Pad10x, 16, 64, 0b1, 1; "pad10* message = 16B output = 64B")]
+#[test_case(Padding::Pad10x1, 17, 100, 0b11, 2; "pad10*1 message = 17B output = 100B")]
+#[test_case(Padding::Pad10x1, 1024, 2048, 0b101010, 6; "pad10*1 message = 1024B output = 2048B")]
+fn test_xoodoo_sponge_incremental_io(
+    padding: Padding,
+    mlen: usize,
+    dlen: usize,
+    domain_seperator: u8,
+    ds_bit_width: usize,
+) {
+    use crate::sponge::XoodooSponge;
+
+    let mut rng = thread_rng();
+
+    let mut msg = vec![0u8; mlen];
+    let mut dig0 = vec![0u8; dlen]; // digest from oneshot absorption
+    let mut dig1 = vec![0u8; dlen]; // digest from incremental absorption
+
+    rng.fill_bytes(&mut msg);
+
+    // oneshot absorption
+    let mut sponge0 = XoodooSponge::<12, 16>::new(padding);
+    sponge0.absorb(&msg);
+    sponge0.finalize(domain_seperator, ds_bit_width);
+    sponge0.squeeze(&mut dig0);
+
+    // incremental absorption and squeezing
+    let mut sponge1 = XoodooSponge::<12, 16>::new(padding);
+
+    let mut off = 0;
+    while off < mlen {
+        let elen = cmp::min(cmp::max(msg[off] as usize & 31, 1), mlen - off);
+
+        sponge1.absorb(&msg[off..(off + elen)]);
+        off += elen;
+    }
+
+    sponge1.finalize(domain_seperator, ds_bit_width);
+    sponge1.absorb(&msg); // absorbing after finalization should have no side effect !
+
+    let mut off = 0;
+    while off < dlen {
+        let elen = cmp::min(cmp::max(dig0[off] as usize & 31, 1), dlen - off);
+
+        sponge1.squeeze(&mut dig1[off..(off + elen)]);
+        off += elen;
+    }
+
+    assert_eq!(dig0, dig1);
+}
+
+/// Test that a duplexing call, on fresh duplex state, outputs same bytes as squeezed from
+/// a sponge, which absorbed same single input block, while subsequent duplexing calls depend
+/// on all previous inputs.
+#[test_case(Padding::Pad10x; "pad10*")]
+#[test_case(Padding::Pad10x1; "pad10*1")]
+fn test_xoodoo_duplex(padding: Padding) {
+    use crate::sponge::{XoodooDuplex, XoodooSponge};
+
+    const RATE: usize = 44;
+
+    let mut rng = thread_rng();
+
+    let mut blk = [0u8; RATE - 1];
+    rng.fill_bytes(&mut blk);
+
+    let mut sponge = XoodooSponge::<6, RATE>::new(padding);
+    let mut out0 = [0u8; RATE];
+    sponge.absorb(&blk);
+    sponge.finalize(0b10, 2);
+    sponge.squeeze(&mut out0);
+
+    let mut duplex0 = XoodooDuplex::<6, RATE>::new(padding);
+    let mut out1 = [0u8; RATE];
+    duplex0.duplex(&blk, 0b10, 2, &mut out1);
+
+    assert_eq!(out0, out1);
+
+    let mut duplex1 = XoodooDuplex::<6, RATE>::new(padding);
+    duplex1.duplex(&blk[..RATE - 2], 0b10, 2, &mut out1);
+
+    duplex0.duplex(&[], 0, 0, &mut out0);
+    duplex1.duplex(&[], 0, 0, &mut out1);
+    assert_ne!(out0, out1);
+}
@@ -0,0 +1,122 @@
+use crate::farfalle::DomainSuffix;
+use crate::xoofff::Xoofff;
+use std::cmp;
+
+/// Xoodoo\[n_r\] being a 384 -bit permutation, deck function output is produced in 48 -bytes chunks
+const BLOCK_SIZE: usize = 48;
+
+/// Xoofff-WBC is a tweakable wide block cipher, obtained by instantiating Farfalle-WBC mode
+/// with Xoofff deck function. It's length preserving i.e. a plaintext of N (>=0) -bytes is
+/// enciphered into a ciphertext of N -bytes, under a tweak of arbitrary byte length, using
+/// an unbalanced four round Feistel network. Flipping any bit of ciphertext garbles the whole
+/// deciphered plaintext.
+///
+/// Given plaintext P, it's split into two parts ( L, R ) and enciphered as
+///
+/// - R0 <- R0 + H_K(L || 0)
+/// - L <- L + G_K(R || 1 ◦ W)
+/// - R <- R + G_K(L || 0 ◦ W)
+/// - L0 <- L0 + H_K(R || 1)
+///
+/// s.t. W is the tweak and X0 denotes first (at max) 48 -bytes of X. Here both H and G are
+/// instantiated with same Xoofff deck function, which are domain separated by number of
+/// strings in input sequence. Deciphering applies these rounds in reverse order.
+///
+/// See algorithm 4 of https://ia.cr/2016/1188 for definition of Farfalle-WBC and
+/// https://ia.cr/2018/767 for Xoofff-WBC.
+#[derive(Clone, Copy)]
+pub struct XoofffWbc {
+    deck: Xoofff, // deck function, with masked key derived, ready to absorb
+}
+
+impl XoofffWbc {
+    /// Create a new instance of Xoofff-WBC, with a key of arbitrary byte length.
+    #[inline(always)]
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            deck: Xoofff::new(key),
+        }
+    }
+
+    /// Given a tweak W of arbitrary byte length, this routine enciphers a buffer of arbitrary
+    /// byte length, in-place.
+    #[inline(always)]
+    pub fn encrypt(&self, tweak: &[u8], buf: &mut [u8]) {
+        let nl = split(buf.len());
+        let gw = self.tweaked(tweak);
+        let (l, r) = buf.split_at_mut(nl);
+
+        let rlen = cmp::min(r.len(), BLOCK_SIZE);
+        prf(self.deck, l, false, &mut r[..rlen]);
+        prf(gw, r, true, l);
+        prf(gw, l, false, r);
+
+        let llen = cmp::min(l.len(), BLOCK_SIZE);
+        prf(self.deck, r, true, &mut l[..llen]);
+    }
+
+    /// Given a tweak W of arbitrary byte length, this routine deciphers a buffer of arbitrary
+    /// byte length, in-place.
+    #[inline(always)]
+    pub fn decrypt(&self, tweak: &[u8], buf: &mut [u8]) {
+        let nl = split(buf.len());
+        let gw = self.tweaked(tweak);
+        let (l, r) = buf.split_at_mut(nl);
+
+        let llen = cmp::min(l.len(), BLOCK_SIZE);
+        prf(self.deck, r, true, &mut l[..llen]);
+        prf(gw, l, false, r);
+        prf(gw, r, true, l);
+
+        let rlen = cmp::min(r.len(), BLOCK_SIZE);
+        prf(self.deck, l, false, &mut r[..rlen]);
+    }
+
+    /// Absorbs tweak W as first string of input sequence, returning deck function state, which
+    /// can be used for evaluating G_K(X ◦ W), for any X.
+    #[inline(always)]
+    fn tweaked(&self, tweak: &[u8]) -> Xoofff {
+        let mut deck = self.deck;
+        deck.absorb(tweak);
+        deck.finalize_suffix(DomainSuffix::EMPTY, 0);
+        deck.restart();
+        deck
+    }
+}
+
+/// Given byte length N of plaintext, this routine computes byte length of left part L s.t.
+/// for short inputs ( i.e. N <= 94 -bytes ), plaintext is split into (almost) equal halves,
+/// while for longer inputs, L || 1 -bit suffix || padding fills whole blocks and number of
+/// ( padded ) blocks in R is a power of 2 i.e. N_L = (q - 2^x) * 48 - 1, where q = ⌈(N + 1) / 48⌉
+/// and x is largest integer s.t. 2^x < q.
+///
+/// This is byte oriented adaptation of split function, defined in algorithm 5 of https://ia.cr/2016/1188,
+/// where short inputs are those with n <= 2b - (|0| + |1|) -bits, b being block width.
+#[inline(always)]
+pub(crate) fn split(n: usize) -> usize {
+    if n <= 2 * BLOCK_SIZE - 2 {
+        n.div_ceil(2)
+    } else {
+        let q = (n + 1).div_ceil(BLOCK_SIZE);
+        let x = usize::BITS - 1 - (q - 1).leading_zeros();
+        (q - (1 << x)) * BLOCK_SIZE - 1
+    }
+}
+
+/// Given deck function state, this routine absorbs message X with 1 -bit suffix and XORs
+/// squeezed output into destination slice i.e. Y <- Y + F_K(X || suffix).
+#[inline(always)]
+fn prf(mut deck: Xoofff, msg: &[u8], suffix: bool, out: &mut [u8]) {
+    deck.absorb(msg);
+    deck.finalize_suffix(DomainSuffix::from_bits(&[suffix]).unwrap(), 0);
+
+    let mut blk = [0u8; BLOCK_SIZE];
+    for chunk in out.chunks_mut(BLOCK_SIZE) {
+        let ks = &mut blk[..chunk.len()];
+        deck.squeeze(ks);
+
+        for (y, k) in chunk.iter_mut().zip(ks.iter()) {
+            *y ^= *k;
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::wbc::XoofffWbc;
+
+/// Xoofff-WBC-AE is an authenticated encryption scheme, obtained by enciphering plaintext,
+/// expanded with τ -many zero bytes, using Xoofff-WBC wide block cipher, while associated
+/// data is used as tweak. When deciphering, redundancy is checked i.e. last τ -bytes must be
+/// zero. Because wide block cipher garbles whole plaintext on a single bit flip in ciphertext,
+/// it provides robust authenticated encryption, with τ -bytes of ciphertext expansion, without
+/// requiring a nonce.
+///
+/// Expansion length τ ( in bytes ) is configurable, defaulting to 16 -bytes.
+///
+/// See algorithm 6 of https://ia.cr/2016/1188 for definition of Farfalle-WBC-AE and
+/// https://ia.cr/2018/767 for Xoofff-WBC-AE.
+#[derive(Clone, Copy)]
+pub struct XoofffWbcAe<const TAU: usize = 16> {
+    wbc: XoofffWbc,
+}
+
+impl<const TAU: usize> XoofffWbcAe<TAU> {
+    /// Create a new instance of Xoofff-WBC-AE, with a key of arbitrary byte length.
+    #[inline(always)]
+    pub fn new(key: &[u8]) -> Self {
+        Self {
+            wbc: XoofffWbc::new(key),
+        }
+    }
+
+    /// Given associated data A, this routine encrypts a buffer in-place s.t. first N (>=0) -bytes
+    /// of buffer hold plaintext P and remaining τ -bytes are reserved for redundancy, getting
+    /// overwritten. After encryption, whole buffer holds ciphertext C s.t. |C| = |P| + τ.
+    #[inline(always)]
+    pub fn encrypt(&self, ad: &[u8], buf: &mut [u8]) {
+        debug_assert!(
+            buf.len() >= TAU,
+            "Buffer must have room for {} -bytes redundancy",
+            TAU
+        );
+
+        let off = buf.len() - TAU;
+        buf[off..].fill(0);
+        self.wbc.encrypt(ad, buf);
+    }
+
+    /// Given associated data A, this routine decrypts a buffer in-place s.t. whole buffer holds
+    /// ciphertext C and after decryption first |C| - τ -bytes of buffer hold plaintext P. Returns
+    /// truth value if redundancy is verified, otherwise whole buffer is zeroed and false is returned.
+    #[inline(always)]
+    pub fn decrypt(&self, ad: &[u8], buf: &mut [u8]) -> bool {
+        if buf.len() < TAU {
+            buf.fill(0);
+            return false;
+        }
+
+        self.wbc.decrypt(ad, buf);
+
+        let off = buf.len() - TAU;
+        let flg = buf[off..].iter().fold(0u8, |acc, &b| acc | b) == 0;
+        if !flg {
+            buf.fill(0);
+        }
+        flg
+    }
+}
@@ -0,0 +1,477 @@
+use crate::xoodoo;
+use crate::xoofff::{bytes_to_le_words, ct_eq, words_to_le_bytes};
+use std::cmp;
+
+/// Xoodoo\[12\] being a 384 -bit permutation, Cyclist state is 48 -bytes wide
+const STATE_SIZE: usize = 48;
+
+/// \# -of rounds for Xoodoo permutation, used in Xoodyak
+const ROUNDS: usize = 12;
+
+/// Byte length of absorbed/ squeezed blocks, in hash mode of Cyclist
+const R_HASH: usize = 16;
+
+/// Byte length of absorbed blocks, in keyed mode of Cyclist
+const R_KIN: usize = 44;
+
+/// Byte length of squeezed ( and encrypted ) blocks, in keyed mode of Cyclist
+const R_KOUT: usize = 24;
+
+/// Byte length of squeezed bytes, which are absorbed back during ratcheting, in keyed mode of Cyclist
+const R_RATCHET: usize = 16;
+
+/// Byte length of key, used in Xoodyak AEAD profile
+pub const KEY_LEN: usize = 16;
+
+/// Byte length of nonce, used in Xoodyak AEAD profile
+pub const NONCE_LEN: usize = 16;
+
+/// Byte length of authentication tag, produced by Xoodyak AEAD profile
+pub const TAG_LEN: usize = 16;
+
+/// Byte length of digest, produced by Xoodyak hash
+pub const DIGEST_LEN: usize = 32;
+
+/// Cyclist mode of operation, instantiated with Xoodoo\[12\] permutation, as described in
+/// section 2 of Xoodyak specification, submitted to NIST LWC standardization effort
+/// https://csrc.nist.gov/CSRC/media/Projects/lightweight-cryptography/documents/finalist-round/updated-spec-doc/xoodyak-spec-final.pdf
+#[derive(Clone, Copy)]
+pub(crate) struct Cyclist {
+    state: [u8; STATE_SIZE], // Xoodoo permutation state, as bytes
+    phase_up: bool,          // is Cyclist in `up` phase ?
+    keyed: bool,             // is Cyclist in `keyed` mode ?
+    r_absorb: usize,         // byte length of absorbed blocks
+    r_squeeze: usize,        // byte length of squeezed blocks
+}
+
+impl Cyclist {
+    /// Create a new instance of Cyclist in hash mode.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self {
+            state: [0u8; STATE_SIZE],
+            phase_up: true,
+            keyed: false,
+            r_absorb: R_HASH,
+            r_squeeze: R_HASH,
+        }
+    }
+
+    /// Create a new instance of Cyclist, given key K, key identifier id and counter, s.t.
+    /// |K| + |id| + 1 <= 44. Following Cyclist(K, id, counter) routine of specification, it
+    /// switches into keyed mode only if K is non-empty, otherwise it stays in hash mode.
+    #[inline(always)]
+    pub fn new_keyed(key: &[u8], id: &[u8], counter: &[u8]) -> Self {
+        let mut cyclist = Self::new();
+        if !key.is_empty() {
+            cyclist.absorb_key(key, id, counter);
+        }
+        cyclist
+    }
+
+    /// AbsorbKey(K, id, counter) routine of Cyclist, switching it into keyed mode.
+    #[inline(always)]
+    fn absorb_key(&mut self, key: &[u8], id: &[u8], counter: &[u8]) {
+        debug_assert!(
+            key.len() + id.len() < R_KIN,
+            "Key and key identifier must together be < {} -bytes",
+            R_KIN
+        );
+
+        self.keyed = true;
+        self.r_absorb = R_KIN;
+        self.r_squeeze = R_KOUT;
+
+        let klen = key.len();
+        let ilen = id.len();
+
+        let mut kid = [0u8; R_KIN];
+        kid[..klen].copy_from_slice(key);
+        kid[klen..(klen + ilen)].copy_from_slice(id);
+        kid[klen + ilen] = ilen as u8;
+
+        self.absorb_any(&kid[..(klen + ilen + 1)], self.r_absorb, 0x02);
+        if !counter.is_empty() {
+            self.absorb_any(counter, 1, 0x00);
+        }
+    }
+
+    /// Absorb(X) routine of Cyclist, absorbing arbitrary many bytes, as a single string.
+    #[inline(always)]
+    pub fn absorb(&mut self, msg: &[u8]) {
+        self.absorb_any(msg, self.r_absorb, 0x03);
+    }
+
+    /// Squeeze(l) routine of Cyclist, squeezing l -bytes into output slice.
+    #[inline(always)]
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        self.squeeze_any(out, 0x40);
+    }
+
+    /// SqueezeKey(l) routine of Cyclist, squeezing l -bytes into output slice, to be used as key.
+    #[inline(always)]
+    pub fn squeeze_key(&mut self, out: &mut [u8]) {
+        debug_assert!(self.keyed, "SqueezeKey is only available in keyed mode");
+        self.squeeze_any(out, 0x20);
+    }
+
+    /// Ratchet() routine of Cyclist, overwriting part of state, so that recovering earlier
+    /// state from current state becomes infeasible.
+    #[inline(always)]
+    pub fn ratchet(&mut self) {
+        debug_assert!(self.keyed, "Ratchet is only available in keyed mode");
+
+        let mut buf = [0u8; R_RATCHET];
+        self.squeeze_any(&mut buf, 0x10);
+        self.absorb_any(&buf, self.r_absorb, 0x00);
+    }
+
+    /// Crypt(I, decrypt) routine of Cyclist, encrypting ( or decrypting ) a buffer in-place,
+    /// in R_kout -bytes blocks, while absorbing plaintext into state.
+    #[inline(always)]
+    pub fn crypt(&mut self, buf: &mut [u8], decrypt: bool) {
+        debug_assert!(self.keyed, "Encrypt/ Decrypt is only available in keyed mode");
+
+        let mut cu = 0x80;
+        let mut off = 0;
+
+        loop {
+            let read = cmp::min(R_KOUT, buf.len() - off);
+
+            let mut ks = [0u8; R_KOUT];
+            self.up(&mut ks[..read], cu);
+
+            let blk = &mut buf[off..(off + read)];
+            if !decrypt {
+                self.down(blk, 0x00);
+            }
+            for (b, k) in blk.iter_mut().zip(ks.iter()) {
+                *b ^= *k;
+            }
+            if decrypt {
+                self.down(blk, 0x00);
+            }
+
+            cu = 0x00;
+            off += read;
+
+            if off == buf.len() {
+                break;
+            }
+        }
+    }
+
+    /// AbsorbAny(X, r, cD) routine of Cyclist, splitting X into r -bytes blocks, processing
+    /// at least one ( possibly empty ) block.
+    #[inline(always)]
+    fn absorb_any(&mut self, msg: &[u8], r: usize, cd: u8) {
+        let mut cd = cd;
+        let mut off = 0;
+
+        loop {
+            let read = cmp::min(r, msg.len() - off);
+
+            if !self.phase_up {
+                self.up(&mut [], 0x00);
+            }
+            self.down(&msg[off..(off + read)], cd);
+
+            cd = 0x00;
+            off += read;
+
+            if off == msg.len() {
+                break;
+            }
+        }
+    }
+
+    /// SqueezeAny(l, cU) routine of Cyclist, squeezing l -bytes into output slice.
+    #[inline(always)]
+    fn squeeze_any(&mut self, out: &mut [u8], cu: u8) {
+        let read = cmp::min(self.r_squeeze, out.len());
+        self.up(&mut out[..read], cu);
+
+        let mut off = read;
+        while off < out.len() {
+            self.down(&[], 0x00);
+
+            let read = cmp::min(self.r_squeeze, out.len() - off);
+            self.up(&mut out[off..(off + read)], 0x00);
+            off += read;
+        }
+    }
+
+    /// Up(Yi, cU) routine of Cyclist, applying permutation on state and extracting first |Yi| -bytes.
+    #[inline(always)]
+    pub fn up(&mut self, out: &mut [u8], cu: u8) {
+        if self.keyed {
+            self.state[STATE_SIZE - 1] ^= cu;
+        }
+
+        let mut words = bytes_to_le_words(&self.state);
+        xoodoo::permute::<ROUNDS>(&mut words);
+        words_to_le_bytes(&words, &mut self.state);
+
+        self.phase_up = true;
+        out.copy_from_slice(&self.state[..out.len()]);
+    }
+
+    /// Down(Xi, cD) routine of Cyclist, adding padded block Xi into state.
+    #[inline(always)]
+    pub fn down(&mut self, blk: &[u8], cd: u8) {
+        for (s, b) in self.state.iter_mut().zip(blk.iter()) {
+            *s ^= *b;
+        }
+        self.state[blk.len()] ^= 0x01;
+        self.state[STATE_SIZE - 1] ^= if self.keyed { cd } else { cd & 0x01 };
+
+        self.phase_up = false;
+    }
+}
+
+/// Xoodyak hash is a hash function ( with extendable output ), obtained by using Cyclist mode
+/// of operation in hash mode, instantiated with Xoodoo\[12\] permutation.
+///
+/// One can absorb arbitrary many message bytes, by calling `absorb` routine arbitrary many
+/// times, which is equivalent to absorbing concatenation of those messages, at once. Once
+/// squeezing starts, calling `absorb` does nothing. Arbitrary many bytes can be squeezed by
+/// calling `squeeze` routine arbitrary many times, first 32 -bytes of which is Xoodyak digest.
+///
+/// See section 3 of Xoodyak specification https://csrc.nist.gov/CSRC/media/Projects/lightweight-cryptography/documents/finalist-round/updated-spec-doc/xoodyak-spec-final.pdf
+#[derive(Clone, Copy)]
+pub struct XoodyakHash {
+    cyclist: Cyclist,  // Cyclist in hash mode
+    blk: [u8; R_HASH], // message/ output block ( buffer )
+    off: usize,        // offset into block buffer
+    first: bool,       // is first message block yet to be absorbed ?
+    squeezing: bool,   // has squeezing started ?
+}
+
+impl Default for XoodyakHash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XoodyakHash {
+    /// Create a new instance of Xoodyak hash, which can be used for incrementally absorbing
+    /// message bytes and squeezing output bytes.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self {
+            cyclist: Cyclist::new(),
+            blk: [0u8; R_HASH],
+            off: 0,
+            first: true,
+            squeezing: false,
+        }
+    }
+
+    /// Given a message of byte length N (>=0), computes its 32 -bytes Xoodyak digest.
+    #[inline(always)]
+    pub fn digest(msg: &[u8]) -> [u8; DIGEST_LEN] {
+        let mut hasher = Self::new();
+        let mut dig = [0u8; DIGEST_LEN];
+
+        hasher.absorb(msg);
+        hasher.squeeze(&mut dig);
+        dig
+    }
+
+    /// Given a message of byte length N (>=0), this routine absorbs it into Cyclist state, while
+    /// buffering last ( possibly full ) block, because it's the one absorbed with domain separator,
+    /// if no other block comes before it.
+    #[inline(always)]
+    pub fn absorb(&mut self, msg: &[u8]) {
+        if self.squeezing {
+            return;
+        }
+
+        let mut off = 0;
+        while off < msg.len() {
+            if self.off == R_HASH {
+                self.absorb_block();
+            }
+
+            let read = cmp::min(R_HASH - self.off, msg.len() - off);
+            self.blk[self.off..(self.off + read)].copy_from_slice(&msg[off..(off + read)]);
+
+            self.off += read;
+            off += read;
+        }
+    }
+
+    /// Given that arbitrary many message bytes are absorbed, this routine can be used for
+    /// squeezing arbitrary many bytes out of Cyclist state. First call to this routine finishes
+    /// absorption of message, after which calling `absorb` does nothing.
+    #[inline(always)]
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        if !self.squeezing {
+            if self.first || self.off > 0 {
+                self.absorb_block();
+            }
+
+            self.cyclist.up(&mut self.blk, 0x40);
+            self.off = 0;
+            self.squeezing = true;
+        }
+
+        let mut off = 0;
+        while off < out.len() {
+            if self.off == R_HASH {
+                self.cyclist.down(&[], 0x00);
+                self.cyclist.up(&mut self.blk, 0x00);
+                self.off = 0;
+            }
+
+            let read = cmp::min(R_HASH - self.off, out.len() - off);
+            out[off..(off + read)].copy_from_slice(&self.blk[self.off..(self.off + read)]);
+
+            self.off += read;
+            off += read;
+        }
+    }
+
+    /// Absorbs buffered message block into Cyclist state, following AbsorbAny(X, R_hash, 0x03).
+    #[inline(always)]
+    fn absorb_block(&mut self) {
+        if !self.cyclist.phase_up {
+            self.cyclist.up(&mut [], 0x00);
+        }
+
+        let cd = if self.first { 0x03 } else { 0x00 };
+        self.cyclist.down(&self.blk[..self.off], cd);
+
+        self.off = 0;
+        self.first = false;
+    }
+}
+
+/// Xoodyak in keyed mode, offering raw Cyclist session API, for protocol designers, s.t. every
+/// squeezed ( or encrypted ) output depends on whole history of earlier operations, on session.
+///
+/// See section 2 of Xoodyak specification https://csrc.nist.gov/CSRC/media/Projects/lightweight-cryptography/documents/finalist-round/updated-spec-doc/xoodyak-spec-final.pdf
+#[derive(Clone, Copy)]
+pub struct XoodyakKeyed {
+    cyclist: Cyclist, // Cyclist in keyed mode
+}
+
+impl XoodyakKeyed {
+    /// Create a new instance of Xoodyak in keyed mode, by absorbing non-empty key K, key
+    /// identifier id and counter ( both of which can be empty ), s.t. |K| + |id| + 1 <= 44.
+    ///
+    /// This is the AbsorbKey(K, id, counter) routine of Cyclist. It's not offered separately,
+    /// because specification invokes it only when Cyclist object is created, with a non-empty
+    /// key, while an empty key keeps Cyclist in hash mode, where encryption, SqueezeKey and
+    /// Ratchet are not available. For hash mode, use `XoodyakHash`.
+    #[inline(always)]
+    pub fn new(key: &[u8], id: &[u8], counter: &[u8]) -> Self {
+        debug_assert!(!key.is_empty(), "Key must be non-empty, for keyed mode");
+
+        Self {
+            cyclist: Cyclist::new_keyed(key, id, counter),
+        }
+    }
+
+    /// Absorbs a message of arbitrary byte length, as a single string.
+    #[inline(always)]
+    pub fn absorb(&mut self, msg: &[u8]) {
+        self.cyclist.absorb(msg);
+    }
+
+    /// Encrypts plaintext P into ciphertext C s.t. |C| = |P|, in-place.
+    #[inline(always)]
+    pub fn encrypt(&mut self, buf: &mut [u8]) {
+        self.cyclist.crypt(buf, false);
+    }
+
+    /// Decrypts ciphertext C into plaintext P s.t. |P| = |C|, in-place.
+    #[inline(always)]
+    pub fn decrypt(&mut self, buf: &mut [u8]) {
+        self.cyclist.crypt(buf, true);
+    }
+
+    /// Squeezes arbitrary many bytes, as a single string, which can be used as tag.
+    #[inline(always)]
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        self.cyclist.squeeze(out);
+    }
+
+    /// Squeezes arbitrary many bytes, as a single string, which can be used as a derived key.
+    #[inline(always)]
+    pub fn squeeze_key(&mut self, out: &mut [u8]) {
+        self.cyclist.squeeze_key(out);
+    }
+
+    /// Ratchets state, so that earlier state can't be recovered, offering forward secrecy.
+    #[inline(always)]
+    pub fn ratchet(&mut self) {
+        self.cyclist.ratchet();
+    }
+}
+
+/// Xoodyak AEAD, following NIST LWC profile, with 16 -bytes key, 16 -bytes nonce and
+/// 16 -bytes tag, which is built on top of Xoodyak keyed mode as
+///
+/// - Cyclist(K, ε, ε)
+/// - Absorb(N)
+/// - Absorb(A)
+/// - C <- Encrypt(P)
+/// - T <- Squeeze(16)
+///
+/// See section 3 of Xoodyak specification https://csrc.nist.gov/CSRC/media/Projects/lightweight-cryptography/documents/finalist-round/updated-spec-doc/xoodyak-spec-final.pdf
+#[derive(Clone, Copy)]
+pub struct XoodyakAead {
+    cyclist: Cyclist, // Cyclist in keyed mode, with key absorbed
+}
+
+impl XoodyakAead {
+    /// Create a new instance of Xoodyak AEAD, with a 16 -bytes key.
+    #[inline(always)]
+    pub fn new(key: &[u8; KEY_LEN]) -> Self {
+        Self {
+            cyclist: Cyclist::new_keyed(key, &[], &[]),
+        }
+    }
+
+    /// Given nonce N and associated data A, this routine encrypts plaintext P in-place,
+    /// returning authentication tag T.
+    #[inline(always)]
+    pub fn encrypt(&self, nonce: &[u8; NONCE_LEN], ad: &[u8], buf: &mut [u8]) -> [u8; TAG_LEN] {
+        let mut cyclist = self.cyclist;
+        let mut tag = [0u8; TAG_LEN];
+
+        cyclist.absorb(nonce);
+        cyclist.absorb(ad);
+        cyclist.crypt(buf, false);
+        cyclist.squeeze(&mut tag);
+
+        tag
+    }
+
+    /// Given nonce N, associated data A and authentication tag T, this routine decrypts
+    /// ciphertext C in-place and verifies T in constant-time, returning truth value. If tag
+    /// doesn't match, buffer is zeroed and false is returned.
+    #[inline(always)]
+    pub fn decrypt(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        ad: &[u8],
+        buf: &mut [u8],
+        tag: &[u8; TAG_LEN],
+    ) -> bool {
+        let mut cyclist = self.cyclist;
+        let mut computed = [0u8; TAG_LEN];
+
+        cyclist.absorb(nonce);
+        cyclist.absorb(ad);
+        cyclist.crypt(buf, true);
+        cyclist.squeeze(&mut computed);
+
+        let flg = ct_eq(&computed, tag);
+        if !flg {
+            buf.fill(0);
+        }
+        flg
+    }
+}